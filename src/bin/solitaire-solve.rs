@@ -0,0 +1,311 @@
+use std::{
+    env, fs,
+    ops::RangeInclusive,
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Instant,
+};
+
+use solitaire_web::{
+    difficulty::Difficulty,
+    game::Game,
+    options::Draw,
+    record::GameRecord,
+    solver::{SolveResult, Solver, MAX_NODES},
+    variant::Klondike,
+};
+
+const USAGE: &str = "\
+Usage: solitaire-solve [options] SEED...
+       solitaire-solve [options] --range START..END
+       solitaire-solve [options] --record FILE...
+
+Options:
+    --draw-one         Solve with draw one rather than draw three (ignored for records)
+    --threads N        Number of deals to solve at once (defaults to the number of CPUs)
+    --max-nodes N      Positions to search before giving up on a deal
+    --format csv|json  Output format (defaults to csv)";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+// Something to solve: a fresh deal, or the position a record leaves off at
+enum Job<'a> {
+    Deal(u64),
+    Record { name: &'a str, game: &'a Game },
+}
+
+struct Report {
+    name: String,
+    seed: u64,
+    draw: Draw,
+    moves_played: usize,
+    result: SolveResult,
+    solution_length: Option<usize>,
+    nodes: usize,
+    difficulty: Option<Difficulty>,
+    millis: u128,
+}
+
+impl Report {
+    fn outcome(&self) -> &'static str {
+        match self.result {
+            SolveResult::Solved(_) => "winnable",
+            SolveResult::Unsolvable => "unwinnable",
+            SolveResult::GaveUp => "unknown",
+        }
+    }
+
+    fn csv_header() -> &'static str {
+        "name,seed,draw,moves_played,result,solution_length,nodes,difficulty,time_ms"
+    }
+
+    fn to_csv(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        // Record paths can have commas or quotes in them, which need the field quoting
+        let name = if self.name.contains(&[',', '"', '\n', '\r'][..]) {
+            format!("\"{}\"", self.name.replace('"', "\"\""))
+        } else {
+            self.name.clone()
+        };
+        format!(
+            "{},{},{},{},{},{},{},{},{}",
+            name,
+            self.seed,
+            self.draw.count(),
+            self.moves_played,
+            self.outcome(),
+            optional(self.solution_length.map(|l| l.to_string())),
+            self.nodes,
+            optional(self.difficulty.map(|d| d.to_string())),
+            self.millis
+        )
+    }
+
+    fn to_json(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| String::from("null"));
+        format!(
+            "{{\"name\":\"{}\",\"seed\":{},\"draw\":{},\"moves_played\":{},\"result\":\"{}\",\
+             \"solution_length\":{},\"nodes\":{},\"difficulty\":{},\"time_ms\":{}}}",
+            self.name.replace('\\', "\\\\").replace('"', "\\\""),
+            self.seed,
+            self.draw.count(),
+            self.moves_played,
+            self.outcome(),
+            optional(self.solution_length.map(|l| l.to_string())),
+            self.nodes,
+            optional(self.difficulty.map(|d| format!("\"{}\"", d))),
+            self.millis
+        )
+    }
+}
+
+struct Args {
+    rules: Klondike,
+    // Seeds are only dealt once a thread gets to them, so long ranges don't fill up memory
+    seeds: Vec<RangeInclusive<u64>>,
+    records: Vec<(String, Game)>,
+    threads: usize,
+    max_nodes: usize,
+    format: Format,
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("\"{}\" is not a valid value for {}", value, flag))
+}
+
+fn load_record(path: &str) -> Result<(String, Game), String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let record = text
+        .parse::<GameRecord>()
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let game = Game::from_record(&record).map_err(|e| format!("{}: {}", path, e))?;
+
+    Ok((path.to_string(), game))
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut draw = Draw::Three;
+        let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+        let mut max_nodes = MAX_NODES;
+        let mut format = Format::Csv;
+        let mut seeds = Vec::new();
+        let mut records = Vec::new();
+        let mut reading_records = false;
+
+        let mut iter = env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--draw-one" => draw = Draw::One,
+                "--threads" => threads = parse_value::<usize>(&arg, iter.next())?.max(1),
+                "--max-nodes" => max_nodes = parse_value(&arg, iter.next())?,
+                "--format" => {
+                    format = match iter.next().as_deref() {
+                        Some("csv") => Format::Csv,
+                        Some("json") => Format::Json,
+                        _ => return Err(String::from("--format should be csv or json")),
+                    }
+                }
+                "--range" => {
+                    let range = iter.next().ok_or("--range needs a value")?;
+                    let (start, end) = range
+                        .split_once("..")
+                        .ok_or_else(|| format!("\"{}\" should look like START..END", range))?;
+                    let start: u64 = parse_value("--range", Some(start.to_string()))?;
+                    let end: u64 = parse_value("--range", Some(end.to_string()))?;
+                    if start < end {
+                        seeds.push(start..=end - 1);
+                    }
+                }
+                // Plain arguments after this are record files rather than seeds
+                "--record" => reading_records = true,
+                _ if arg.starts_with("--") => return Err(format!("Unknown option \"{}\"", arg)),
+                _ if reading_records => records.push(arg),
+                _ => {
+                    let seed: u64 = parse_value("SEED", Some(arg))?;
+                    seeds.push(seed..=seed);
+                }
+            }
+        }
+
+        let records = records
+            .iter()
+            .map(|path| load_record(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let args = Self {
+            rules: Klondike::new(draw),
+            seeds,
+            records,
+            threads,
+            max_nodes,
+            format,
+        };
+        if args.job_count() == 0 {
+            return Err(String::from("Nothing to solve"));
+        }
+        Ok(args)
+    }
+
+    fn job_count(&self) -> usize {
+        let seeds = self
+            .seeds
+            .iter()
+            .map(|range| range.end() - range.start() + 1);
+        seeds.sum::<u64>() as usize + self.records.len()
+    }
+
+    // Seeds come first, in the order they were given, then records
+    fn job(&self, mut index: usize) -> Option<Job<'_>> {
+        for range in self.seeds.iter() {
+            let len = (range.end() - range.start() + 1) as usize;
+            if index < len {
+                return Some(Job::Deal(range.start() + index as u64));
+            }
+            index -= len;
+        }
+        self.records
+            .get(index)
+            .map(|(name, game)| Job::Record { name, game })
+    }
+}
+
+fn solve(job: Job, rules: Klondike, max_nodes: usize) -> Report {
+    let dealt;
+    let (name, game) = match job {
+        Job::Deal(seed) => {
+            dealt = Game::deal(rules, seed);
+            (seed.to_string(), &dealt)
+        }
+        Job::Record { name, game } => (name.to_string(), game),
+    };
+
+    let start = Instant::now();
+    let mut solver = Solver::from_game(game).with_node_limit(max_nodes);
+    let result = loop {
+        if let Some(result) = solver.step(usize::MAX) {
+            break result;
+        }
+    };
+    let millis = start.elapsed().as_millis();
+
+    let stats = solver.stats();
+    let solved = matches!(result, SolveResult::Solved(_));
+    // Ratings are for whole deals, so a position part way through a game isn't rated
+    let fresh_deal = game.moves().is_empty();
+
+    Report {
+        name,
+        seed: game.seed(),
+        draw: game.draw(),
+        moves_played: game.moves().len(),
+        solution_length: Some(stats.solution_length).filter(|_| solved),
+        nodes: stats.nodes,
+        difficulty: Some(Difficulty::rate(&stats)).filter(|_| solved && fresh_deal),
+        result,
+        millis,
+    }
+}
+
+// Each thread takes the next job until there are none left
+fn solve_all(args: &Args) -> Vec<Report> {
+    let next = AtomicUsize::new(0);
+    let reports = Mutex::new(Vec::new());
+
+    thread::scope(|scope| {
+        for _ in 0..args.threads.min(args.job_count()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let job = match args.job(index) {
+                    Some(job) => job,
+                    None => break,
+                };
+                let report = solve(job, args.rules, args.max_nodes);
+                reports
+                    .lock()
+                    .expect("no thread should panic")
+                    .push((index, report));
+            });
+        }
+    });
+
+    let mut reports = reports.into_inner().expect("no thread should panic");
+    reports.sort_by_key(|(index, _)| *index);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+fn main() {
+    let args = Args::parse().unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    let reports = solve_all(&args);
+
+    match args.format {
+        Format::Csv => {
+            println!("{}", Report::csv_header());
+            for report in reports.iter() {
+                println!("{}", report.to_csv());
+            }
+        }
+        Format::Json => {
+            let rows = reports
+                .iter()
+                .map(|r| format!("  {}", r.to_json()))
+                .collect::<Vec<_>>();
+            println!("[\n{}\n]", rows.join(",\n"));
+        }
+    }
+}
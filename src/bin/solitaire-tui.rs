@@ -0,0 +1,236 @@
+use std::{
+    env,
+    io::{self, Write},
+    process,
+};
+
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use solitaire_web::{
+    card::{Card, SuitColour},
+    deal,
+    game::Game,
+    moves::Move,
+    options::Draw,
+};
+
+// Every card is drawn this many characters wide, e.g. "10♥ "
+const CARD_WIDTH: u16 = 5;
+
+const TABLEAUS_ROW: u16 = 5;
+
+const USAGE: &str = "Usage: solitaire-tui [--seed N] [--draw-one]";
+
+const HELP: &str = "Type a move such as W-T3, T5(3)-T1 or T2-F1 and press Enter. \
+    Enter on its own draws from the stock. \"new\" deals another game and \"quit\" exits.";
+
+struct Args {
+    seed: Option<u64>,
+    draw: Draw,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut args = Args {
+            seed: None,
+            draw: Draw::default(),
+        };
+
+        let mut iter = env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--seed" => {
+                    let value = iter.next().ok_or("--seed needs a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("\"{}\" is not a valid seed", value))?;
+                    args.seed = Some(seed);
+                }
+                "--draw-one" => args.draw = Draw::One,
+                _ => return Err(format!("Unknown argument \"{}\"", arg)),
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+struct Tui {
+    game: Game,
+    input: String,
+    message: String,
+}
+
+impl Tui {
+    fn new(game: Game) -> Self {
+        Self {
+            game,
+            input: String::new(),
+            message: String::from(HELP),
+        }
+    }
+
+    fn print_card(out: &mut impl Write, card: Card) -> io::Result<()> {
+        let Card(value, suit) = card;
+        let colour = match suit.colour() {
+            SuitColour::Red => Color::Red,
+            SuitColour::Black => Color::Reset,
+        };
+        let text = format!("{}{}", value, suit);
+        queue!(
+            out,
+            SetForegroundColor(colour),
+            Print(format!("{:<width$}", text, width = CARD_WIDTH as usize)),
+            ResetColor
+        )
+    }
+
+    fn print_at(out: &mut impl Write, column: u16, row: u16, text: &str) -> io::Result<()> {
+        queue!(out, cursor::MoveTo(column, row), Print(text))
+    }
+
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, Clear(ClearType::All))?;
+
+        let title = format!(
+            "Deal {}, draw {}, {} moves",
+            self.game.seed(),
+            self.game.draw().count(),
+            self.game.moves().len()
+        );
+        Self::print_at(out, 0, 0, &title)?;
+
+        // Stock, waste and foundations along the top, as on the web version
+        Self::print_at(out, 0, 2, "Stock  W")?;
+        Self::print_at(out, 0, 3, &format!("[{:>2}]", self.game.stock().len()))?;
+        queue!(out, cursor::MoveTo(7, 3))?;
+        let waste = self.game.waste();
+        let shown = &waste[waste.len().saturating_sub(self.game.draw().count())..];
+        if shown.is_empty() {
+            queue!(out, Print("--"))?;
+        }
+        for card in shown {
+            Self::print_card(out, *card)?;
+        }
+
+        for (i, foundation) in self.game.foundations().iter().enumerate() {
+            let column = 24 + i as u16 * CARD_WIDTH;
+            Self::print_at(out, column, 2, &format!("F{}", i + 1))?;
+            queue!(out, cursor::MoveTo(column, 3))?;
+            match foundation.last() {
+                Some(card) => Self::print_card(out, *card)?,
+                None => queue!(out, Print("--"))?,
+            }
+        }
+
+        for (i, tableau) in self.game.tableaus().iter().enumerate() {
+            let column = i as u16 * CARD_WIDTH;
+            Self::print_at(out, column, TABLEAUS_ROW, &format!("T{}", i + 1))?;
+            if tableau.cards().is_empty() {
+                Self::print_at(out, column, TABLEAUS_ROW + 1, "--")?;
+            }
+            for (row, card) in tableau.cards().iter().enumerate() {
+                queue!(out, cursor::MoveTo(column, TABLEAUS_ROW + 1 + row as u16))?;
+                if row < tableau.face_down() {
+                    queue!(out, Print("##"))?;
+                } else {
+                    Self::print_card(out, *card)?;
+                }
+            }
+        }
+
+        let tallest = self
+            .game
+            .tableaus()
+            .iter()
+            .map(|t| t.cards().len().max(1))
+            .max()
+            .unwrap_or(1) as u16;
+        let prompt_row = TABLEAUS_ROW + tallest + 2;
+        Self::print_at(out, 0, prompt_row + 1, &self.message)?;
+        Self::print_at(out, 0, prompt_row, &format!("> {}", self.input))?;
+
+        out.flush()
+    }
+
+    fn submit(&mut self) -> bool {
+        let command = self.input.trim().to_uppercase();
+        self.input.clear();
+
+        self.message = match command.as_str() {
+            "QUIT" | "Q" => return false,
+            "NEW" | "N" => {
+                self.game = Game::new(deal::random_seed(), self.game.draw());
+                String::from(HELP)
+            }
+            "" => match self.game.deal_from_stock() {
+                Some(_) => String::new(),
+                None => String::from("The stock and waste are both empty"),
+            },
+            _ => match command.parse::<Move>() {
+                Ok(game_move) => match self.game.play(game_move) {
+                    Ok(()) if self.game.is_won() => format!(
+                        "You won in {} moves! Type \"new\" to play again.",
+                        self.game.moves().len()
+                    ),
+                    Ok(()) => String::new(),
+                    Err(error) => error.to_string(),
+                },
+                Err(()) => format!("\"{}\" is not a move. {}", command, HELP),
+            },
+        };
+        true
+    }
+
+    // Returns false once the player has asked to quit
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Esc => self.input.clear(),
+            KeyCode::Enter => return self.submit(),
+            _ => {}
+        }
+        true
+    }
+
+    fn run(&mut self, out: &mut impl Write) -> io::Result<()> {
+        loop {
+            self.draw(out)?;
+
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !self.handle_key(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+fn main() -> io::Result<()> {
+    let args = Args::parse().unwrap_or_else(|error| {
+        eprintln!("{}\n{}", error, USAGE);
+        process::exit(2);
+    });
+    let seed = args.seed.unwrap_or_else(deal::random_seed);
+    let mut tui = Tui::new(Game::new(seed, args.draw));
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    execute!(out, EnterAlternateScreen)?;
+
+    let result = tui.run(&mut out);
+
+    // Put the terminal back however the game ended
+    execute!(out, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
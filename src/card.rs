@@ -3,11 +3,6 @@ use std::fmt;
 use rand::distributions::{Distribution, Standard};
 use rand::Rng;
 
-use yew::{html, Html};
-
-use crate::util::Bounds;
-use crate::{CardSinks, CardSources, CARD_HEIGHT, CARD_WIDTH, STACKED_CARD_Y_STRIDE};
-
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Suit {
     Spades,
@@ -75,6 +70,11 @@ pub enum Value {
 }
 
 impl Value {
+    /// The value as a number, from 1 for an Ace up to 13 for a King.
+    pub fn number(&self) -> usize {
+        *self as usize + 1
+    }
+
     pub fn next_value(&self) -> Option<Value> {
         match self {
             Value::Ace => Some(Value::Two),
@@ -110,6 +110,15 @@ impl Value {
             Value::King => Some(Value::Queen),
         }
     }
+
+    /// Like `next_value`, but going round from King to Ace, for games where runs wrap around.
+    pub fn next_value_wrapping(&self) -> Value {
+        self.next_value().unwrap_or(Value::Ace)
+    }
+
+    pub fn prev_value_wrapping(&self) -> Value {
+        self.prev_value().unwrap_or(Value::King)
+    }
 }
 
 impl fmt::Display for Value {
@@ -214,305 +223,13 @@ pub const DECK: &[Card] = &[
     Card(Value::King, Suit::Diamonds),
 ];
 
-pub enum CardVisual {
-    Card(Card),
-    Flipped,
-    EmptySlot,
-    Invisible,
-}
-
-impl CardVisual {
-    fn as_html_custom_style(&self, custom_style: String, identifier: String) -> Html {
-        let element_name = format!("div-{}", identifier);
-        match self {
-            Self::Flipped => {
-                html! { <@{element_name} class="card flipped-card" style={ custom_style } /> }
-            }
-            Self::EmptySlot => {
-                html! { <div class="card empty-slot" style={ custom_style } />}
-            }
-            Self::Card(card) => {
-                let Card(value, suit) = card;
-
-                let card_class = match suit {
-                    Suit::Clubs | Suit::Spades => "card card-base card-black",
-                    Suit::Hearts | Suit::Diamonds => "card card-base card-red",
-                };
-
-                html! {
-                    <@{element_name} class={{card_class}} style={ custom_style }>
-                        <span class="card-logo">{ value }<br/>{ suit }</span>
-                        {{ Self::picture_html(card) }}
-                        <span class="card-logo card-logo-flipped">{ value }<br/>{ suit }</span>
-                    </@>
-                }
-            }
-            Self::Invisible => html! {},
-        }
-    }
-
-    pub fn as_html(&self, x: i32, y: i32, identifier: String) -> Html {
-        self.as_html_custom(x, y, None, None, identifier)
-    }
-    pub fn as_draggable_html(&self, x: i32, y: i32, identifier: String) -> Html {
-        self.as_html_custom(x, y, None, "cursor:move;", identifier)
-    }
-    pub fn as_draggable_html_from(
-        &self,
-        from_x: i32,
-        from_y: i32,
-        to_x: i32,
-        to_y: i32,
-        identifier: String,
-    ) -> Html {
-        self.as_html_custom(
-            to_x,
-            to_y,
-            Some((from_x, from_y)),
-            "cursor:move;",
-            identifier,
-        )
-    }
-    pub fn as_clickable_html(&self, x: i32, y: i32, identifier: String) -> Html {
-        self.as_html_custom(x, y, None, "cursor:pointer;", identifier)
-    }
-
-    fn as_html_custom<'a, OS: Into<Option<&'a str>>>(
-        &self,
-        x: i32,
-        y: i32,
-        from: Option<(i32, i32)>,
-        custom_style: OS,
-        identifier: String,
-    ) -> Html {
-        let position_part = format!("left:{}px;top:{}px;", x, y);
-        let animation_part = from.map_or(String::new(), |(sx, sy)| {
-            format!(
-                "--start-left:{}px;--start-top:{}px;animation:movingCard 0.2s linear 0s 1 forwards;",
-                sx, sy
-            )
-        });
-        let custom_part = custom_style.into().unwrap_or("");
-        self.as_html_custom_style(
-            format!("{}{}{}", position_part, animation_part, custom_part),
-            identifier,
-        )
-    }
-
-    fn picture_html(card: &Card) -> Html {
-        let Card(value, suit) = card;
-        let e = &suit.to_string();
-        match value {
-            Value::Ace => html! {
-                <span class="card-pic-single-letter">{{e}}</span>
-            },
-            Value::Two => html! {
-                <span class="card-pic-number">{{e}}<br/>{{e}}</span>
-            },
-            Value::Three => html! {
-                <span class="card-pic-number">
-                    {{e}}<br/>
-                    {{e}}<br/>
-                    {{e}}
-                </span>
-            },
-            Value::Four => html! {
-                <span class="card-pic-number">
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}
-                </span>
-            },
-            Value::Five => html! {
-                <span class="card-pic-number">
-                    {{e}}{{e}}<br/>
-                    {{e}}<br/>
-                    {{e}}{{e}}
-                </span>
-            },
-            Value::Six => html! {
-                <span class="card-pic-number">
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}</span>
-            },
-            Value::Seven => html! {
-                <span class="card-pic-number">
-                    {{e}}{{e}}<br/>
-                    {{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}
-                </span>
-            },
-            Value::Eight => html! {
-                <span class="card-pic-number">
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}</span>
-            },
-            Value::Nine => html! {
-                <span class="card-pic-number">
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}
-                </span>
-            },
-            Value::Ten => html! {
-                <span class="card-pic-number">
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}<br/>
-                    {{e}}{{e}}
-                </span>
-            },
-            _ => html! {
-                <span class="card-pic-single-letter">{ value }</span>
-            },
-        }
-    }
-}
-
-#[derive(Debug)]
-pub struct PhysicalCard {
-    x: i32,
-    y: i32,
-    prev_x: i32,
-    prev_y: i32,
-    visible: bool,
-    flipped: bool,
-    card: Card,
-    identifier: String,
-}
-
-impl PhysicalCard {
-    pub fn new(x: i32, y: i32, card: &Card) -> Self {
-        Self {
-            x,
-            y,
-            prev_x: x,
-            prev_y: y,
-            visible: true,
-            flipped: false,
-            card: *card,
-            identifier: rand::random::<u64>().to_string(),
-        }
-    }
-
-    fn card_visual(&self) -> CardVisual {
-        if !self.visible {
-            CardVisual::Invisible
-        } else if self.flipped {
-            CardVisual::Flipped
-        } else {
-            CardVisual::Card(self.card)
-        }
-    }
-
-    pub fn set_visible(&mut self, visible: bool) {
-        self.visible = visible;
-    }
-
-    pub fn flipped(&self) -> bool {
-        self.flipped
-    }
-
-    pub fn set_flipped(&mut self, flipped: bool) {
-        self.flipped = flipped;
-    }
-
-    pub fn set_prev_loc(&mut self, x: i32, y: i32) {
-        self.prev_x = x;
-        self.prev_y = y;
-    }
-
-    pub fn card(&self) -> Card {
-        self.card
-    }
-
-    pub fn position(&self) -> (i32, i32) {
-        (self.x, self.y)
-    }
-
-    pub fn set_xy(&mut self, x: i32, y: i32) {
-        self.x = x;
-        self.y = y;
-    }
-
-    pub fn set_position(&mut self, x: i32, y: i32) {
-        self.set_prev_loc(x, y);
-        self.set_xy(x, y);
-    }
-
-    pub fn move_to(&mut self, x: i32, y: i32) {
-        self.set_prev_loc(self.x, self.y);
-        self.set_xy(x, y);
-
-        // Resetting identifier causes yew to regenerate the div (rather than reuse the existing one)
-        // Which causes animations to restart.
-        self.identifier = rand::random::<u64>().to_string();
-    }
-
-    pub fn within_bounds(&self, x: i32, y: i32) -> bool {
-        Bounds::new(self.x, self.y, CARD_WIDTH, CARD_HEIGHT).contains(x, y)
-    }
-
-    pub fn as_html(&self) -> Html {
-        self.card_visual()
-            .as_html(self.x, self.y, self.identifier.clone())
-    }
-
-    pub fn as_draggable_html(&self) -> Html {
-        self.card_visual()
-            .as_draggable_html(self.x, self.y, self.identifier.clone())
-    }
-
-    pub fn as_clickable_html(&self) -> Html {
-        self.card_visual()
-            .as_clickable_html(self.x, self.y, self.identifier.clone())
-    }
-}
-
-pub trait CardSource {
-    fn card_source(&self) -> CardSources;
-
-    // This is only cards that can be picked up
-    fn borrow_cards(&self, count: usize) -> Vec<&PhysicalCard>;
-    fn borrow_cards_mut(&mut self, count: usize) -> Vec<&mut PhysicalCard>;
-
-    fn take_cards(&mut self, count: usize) -> Vec<PhysicalCard>;
-    fn peek_cards(&self, count: usize) -> Vec<Card> {
-        self.borrow_cards(count)
-            .into_iter()
-            .map(PhysicalCard::card)
-            .collect()
-    }
-
-    fn how_many_cards(&self, mouse_x: i32, mouse_y: i32) -> usize;
-
-    fn set_release_location(&mut self, x: i32, y: i32, count: usize) {
-        for (i, physical_card) in self.borrow_cards_mut(count).into_iter().enumerate() {
-            physical_card.set_prev_loc(x, y + i as i32 * STACKED_CARD_Y_STRIDE);
-        }
-    }
-
-    fn set_mouse_release_location(&mut self, x: i32, y: i32, count: usize) {
-        let x = x - CARD_WIDTH as i32 / 2;
-        let y = y - CARD_HEIGHT as i32 / 2;
-        self.set_release_location(x, y, count);
-    }
-}
-
-pub trait CardSink {
-    fn card_sink(&self) -> CardSinks;
-    fn place_cards(
-        &mut self,
-        mouse_x: i32,
-        mouse_y: i32,
-        physical_cards: Vec<PhysicalCard>,
-    ) -> Result<(), ()>;
-    fn within_bounds(&self, x: i32, y: i32) -> bool;
-    fn is_placement_possible(&self, cards: &[Card]) -> bool;
+/// `count` decks' worth of cards using only `suits`, with each suit repeated to make up the
+/// numbers. Spider is played with two decks of one, two or four suits.
+pub fn decks(count: usize, suits: &[Suit]) -> Vec<Card> {
+    let copies = count * DECK.len() / (suits.len() * 13);
+    DECK.iter()
+        .filter(|Card(_, suit)| suits.contains(suit))
+        .copied()
+        .collect::<Vec<_>>()
+        .repeat(copies)
 }
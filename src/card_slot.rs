@@ -0,0 +1,135 @@
+use std::rc::Rc;
+
+use solitaire_web::{
+    card::Card,
+    variant::{PileKind, Variant},
+};
+use yew::{html, Html};
+
+use crate::{
+    physical_card::{CardSink, CardSource, CardVisual, PhysicalCard},
+    util::Bounds,
+    CARD_HEIGHT, CARD_WIDTH,
+};
+
+/// A space that holds at most one card: a free cell, or a place in a pyramid.
+pub struct CardSlot {
+    kind: PileKind,
+    variant: Rc<dyn Variant>,
+    card: Option<PhysicalCard>,
+    x: i32,
+    y: i32,
+}
+
+impl CardSlot {
+    pub fn new(x: i32, y: i32, kind: PileKind, variant: Rc<dyn Variant>) -> Self {
+        Self {
+            kind,
+            variant,
+            card: None,
+            x,
+            y,
+        }
+    }
+
+    pub fn card(&self) -> Option<Card> {
+        self.card.as_ref().map(PhysicalCard::card)
+    }
+
+    pub fn turn_face_up(&mut self) {
+        if let Some(card) = &mut self.card {
+            card.set_flipped(false);
+        }
+    }
+
+    pub fn deal_card(&mut self, mut card: PhysicalCard, face_up: bool, animate: bool) {
+        if animate {
+            card.move_to(self.x, self.y);
+        } else {
+            card.set_position(self.x, self.y);
+        }
+        card.set_flipped(!face_up);
+        self.card = Some(card);
+    }
+
+    pub fn as_html(&self) -> Html {
+        let card_html = match &self.card {
+            Some(card) => card.as_draggable_html(),
+            None => html! {},
+        };
+
+        html! {
+            <>
+                { CardVisual::EmptySlot.as_html(self.x, self.y, String::new()) }
+                { card_html }
+            </>
+        }
+    }
+}
+
+impl CardSource for CardSlot {
+    fn borrow_cards(&self, count: usize) -> Vec<&PhysicalCard> {
+        self.card.iter().take(count).collect()
+    }
+
+    fn borrow_cards_mut(&mut self, count: usize) -> Vec<&mut PhysicalCard> {
+        self.card.iter_mut().take(count).collect()
+    }
+
+    fn take_cards(&mut self, count: usize) -> Vec<PhysicalCard> {
+        if count > 0 {
+            self.card.take().into_iter().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn how_many_cards(&self, mouse_x: i32, mouse_y: i32) -> usize {
+        match &self.card {
+            Some(card) if card.within_bounds(mouse_x, mouse_y) && !card.flipped() => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl CardSink for CardSlot {
+    fn place_cards(
+        &mut self,
+        mouse_x: i32,
+        mouse_y: i32,
+        mut physical_cards: Vec<PhysicalCard>,
+    ) -> Result<(), ()> {
+        if self.is_placement_possible(
+            &physical_cards
+                .iter()
+                .map(PhysicalCard::card)
+                .collect::<Vec<Card>>(),
+        ) {
+            // Placement is only possible if there is one card
+            let mut physical_card = physical_cards.pop().expect("card should be present");
+            physical_card.set_xy(self.x, self.y);
+            physical_card.set_prev_loc(
+                mouse_x - CARD_WIDTH as i32 / 2,
+                mouse_y - CARD_HEIGHT as i32 / 2,
+            );
+            physical_card.set_visible(true);
+            self.card = Some(physical_card);
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn bounds(&self) -> Bounds {
+        Bounds::new(self.x, self.y, CARD_WIDTH, CARD_HEIGHT)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.card.is_none()
+    }
+
+    fn is_placement_possible(&self, cards: &[Card]) -> bool {
+        self.variant
+            .builds_on(self.kind, cards, self.card.as_ref().map(PhysicalCard::card))
+    }
+}
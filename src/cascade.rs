@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use rand::{thread_rng, Rng};
+use solitaire_web::card::Card;
+use yew::{html, Html};
+
+use crate::{
+    physical_card::{CardVisual, PhysicalCard},
+    CARD_HEIGHT, CARD_WIDTH,
+};
+
+// Physics is done in pixels and milliseconds
+const GRAVITY: f64 = 0.002;
+const BOUNCE_DAMPING: f64 = 0.8;
+const LAUNCH_INTERVAL_MS: f64 = 300.0;
+
+// Every card position drawn leaves a copy behind, so the number of copies has to be capped to
+// keep the page responsive.
+const MAX_TRAIL_LENGTH: usize = 600;
+
+struct BouncingCard {
+    order: usize,
+    foundation: usize,
+    card: PhysicalCard,
+    x: f64,
+    y: f64,
+    vx: f64,
+    vy: f64,
+}
+
+impl BouncingCard {
+    fn step(&mut self, dt: f64, floor: f64) {
+        self.vy += GRAVITY * dt;
+        self.x += self.vx * dt;
+        self.y += self.vy * dt;
+
+        if self.y > floor {
+            self.y = floor;
+            self.vy = -self.vy * BOUNCE_DAMPING;
+        }
+    }
+
+    fn off_screen(&self, width: f64) -> bool {
+        self.x + (CARD_WIDTH as f64) < 0.0 || self.x > width
+    }
+}
+
+/// The bouncing cards shown once every card has been moved onto the foundations.
+pub struct Cascade {
+    flying: Vec<BouncingCard>,
+    // Cards that have left the screen
+    landed: Vec<BouncingCard>,
+    launched: usize,
+    trail: VecDeque<(Card, i32, i32)>,
+    trail_count: usize,
+    last_frame: Option<f64>,
+    last_launch: Option<f64>,
+    width: f64,
+    floor: f64,
+}
+
+impl Cascade {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self {
+            flying: Vec::new(),
+            landed: Vec::new(),
+            launched: 0,
+            trail: VecDeque::new(),
+            trail_count: 0,
+            last_frame: None,
+            last_launch: None,
+            width,
+            floor: height - CARD_HEIGHT as f64,
+        }
+    }
+
+    pub fn should_launch(&self, timestamp: f64) -> bool {
+        match self.last_launch {
+            Some(last) => timestamp - last >= LAUNCH_INTERVAL_MS,
+            None => true,
+        }
+    }
+
+    pub fn launch(&mut self, foundation: usize, card: PhysicalCard, timestamp: f64) {
+        let mut rng = thread_rng();
+        let (x, y) = card.position();
+        let speed = rng.gen_range(0.15..0.45);
+        let direction = if rng.gen::<bool>() { 1.0 } else { -1.0 };
+
+        self.flying.push(BouncingCard {
+            order: self.launched,
+            foundation,
+            card,
+            x: x as f64,
+            y: y as f64,
+            vx: speed * direction,
+            vy: -rng.gen_range(0.0..0.4),
+        });
+        self.launched += 1;
+        self.last_launch = Some(timestamp);
+    }
+
+    /// Moves every flying card along to where it should be at `timestamp`.
+    pub fn advance(&mut self, timestamp: f64) {
+        let dt = self.last_frame.map_or(0.0, |last| timestamp - last);
+        self.last_frame = Some(timestamp);
+
+        let (width, floor) = (self.width, self.floor);
+        for bouncing_card in self.flying.iter_mut() {
+            bouncing_card.step(dt, floor);
+        }
+
+        let (off_screen, on_screen) = self
+            .flying
+            .drain(..)
+            .partition::<Vec<BouncingCard>, _>(|c| c.off_screen(width));
+        self.flying = on_screen;
+        self.landed.extend(off_screen);
+
+        for bouncing_card in self.flying.iter() {
+            self.trail.push_back((
+                bouncing_card.card.card(),
+                bouncing_card.x as i32,
+                bouncing_card.y as i32,
+            ));
+            self.trail_count += 1;
+        }
+        while self.trail.len() > MAX_TRAIL_LENGTH {
+            self.trail.pop_front();
+        }
+    }
+
+    pub fn is_flying(&self) -> bool {
+        !self.flying.is_empty()
+    }
+
+    /// Returns every card that has been launched, in the order they left their foundations.
+    pub fn into_cards(self) -> Vec<(usize, PhysicalCard)> {
+        let mut cards = self.landed;
+        cards.extend(self.flying);
+        cards.sort_by_key(|c| c.order);
+        cards.into_iter().map(|c| (c.foundation, c.card)).collect()
+    }
+
+    pub fn as_html(&self) -> Html {
+        // Trail copies are numbered from the start of the cascade, so each one keeps the same
+        // element while older copies are dropped from the front.
+        let first_index = self.trail_count - self.trail.len();
+        html! {
+            <div class="cascade">
+                { for self.trail.iter().enumerate().map(|(i, (card, x, y))|
+                    CardVisual::Card(*card).as_html(*x, *y, format!("trail-{}", first_index + i))
+                )}
+            </div>
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::{fmt, str::FromStr};
+
+use solitaire_web::options::Draw;
+use yew::{html, ComponentLink, Html};
+
+use crate::{
+    util::{format_time, local_storage},
+    Model, Msg,
+};
+
+// Everyone plays the daily deal with the same rules, whatever their own draw setting is
+pub const DAILY_DRAW: Draw = Draw::Three;
+
+const STORAGE_KEY_PREFIX: &str = "solitaire-daily-";
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+#[derive(Clone, Copy, Debug)]
+pub enum CalendarMsg {
+    PreviousMonth,
+    NextMonth,
+    Play(Date),
+}
+
+/// A day in the local calendar. Months and days are numbered from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today() -> Self {
+        let now = js_sys::Date::new_0();
+        Self {
+            year: now.get_full_year() as i32,
+            month: now.get_month() + 1,
+            day: now.get_date(),
+        }
+    }
+
+    /// The first seed tried for this date's daily deal. Seeds after it are tried in turn until
+    /// one can be won, for up to a thousand tries so they never reach the next day's seeds.
+    pub fn seed(&self) -> u64 {
+        // Reads as the date itself, e.g. 20240131000, which is easy to check against an exported
+        // record
+        (self.year as u64 * 10000 + self.month as u64 * 100 + self.day as u64) * 1000
+    }
+
+    fn is_leap_year(year: i32) -> bool {
+        (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+    }
+
+    fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            _ => 31,
+        }
+    }
+
+    // 0 is Sunday. Uses Sakamoto's method.
+    fn weekday(&self) -> u32 {
+        const OFFSETS: [i32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let year = if self.month < 3 {
+            self.year - 1
+        } else {
+            self.year
+        };
+        let day = year + year / 4 - year / 100
+            + year / 400
+            + OFFSETS[self.month as usize - 1]
+            + self.day as i32;
+        day.rem_euclid(7) as u32
+    }
+
+    fn storage_key(&self) -> String {
+        format!("{}{}", STORAGE_KEY_PREFIX, self)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// How far the player got with a daily deal.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DailyResult {
+    pub won: bool,
+    pub seconds: u32,
+    pub moves: usize,
+}
+
+impl DailyResult {
+    pub fn load(date: Date) -> Option<Self> {
+        local_storage()?
+            .get_item(&date.storage_key())
+            .ok()
+            .flatten()?
+            .parse()
+            .ok()
+    }
+
+    /// Stores the result for `date`, unless it would replace a win with a game that isn't one.
+    pub fn save(&self, date: Date) {
+        if !self.won && Self::load(date).is_some_and(|r| r.won) {
+            return;
+        }
+
+        if let Some(storage) = local_storage() {
+            // Storage can be full or disabled, in which case the result just isn't kept
+            let _ = storage.set_item(&date.storage_key(), &self.to_string());
+        }
+    }
+}
+
+// Stored as e.g. "won 312 87": whether the deal was won, the seconds taken and the moves made
+impl fmt::Display for DailyResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.won { "won" } else { "played" };
+        write!(f, "{} {} {}", state, self.seconds, self.moves)
+    }
+}
+
+impl FromStr for DailyResult {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let won = match parts.next() {
+            Some("won") => true,
+            Some("played") => false,
+            _ => return Err(()),
+        };
+        let seconds = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        let moves = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+
+        Ok(Self {
+            won,
+            seconds,
+            moves,
+        })
+    }
+}
+
+/// A month of daily deals to pick from.
+pub struct Calendar {
+    year: i32,
+    month: u32,
+    today: Date,
+}
+
+impl Calendar {
+    pub fn this_month() -> Self {
+        let today = Date::today();
+        Self {
+            year: today.year,
+            month: today.month,
+            today,
+        }
+    }
+
+    pub fn previous_month(&mut self) {
+        if self.month == 1 {
+            self.year -= 1;
+            self.month = 12;
+        } else {
+            self.month -= 1;
+        }
+    }
+
+    pub fn next_month(&mut self) {
+        // Deals in the future can't be played yet, so there's no point showing them
+        if (self.year, self.month) >= (self.today.year, self.today.month) {
+            return;
+        }
+
+        if self.month == 12 {
+            self.year += 1;
+            self.month = 1;
+        } else {
+            self.month += 1;
+        }
+    }
+
+    fn day_html(&self, date: Date, link: &ComponentLink<Model>) -> Html {
+        let result = DailyResult::load(date);
+        let (class, title) = match result {
+            Some(r) if r.won => (
+                "won",
+                format!("Won in {} with {} moves", format_time(r.seconds), r.moves),
+            ),
+            Some(r) => ("played", format!("Played {} moves", r.moves)),
+            None => ("", String::new()),
+        };
+
+        html! {
+            <td>
+                <button
+                    class=class
+                    title=title
+                    disabled={ date > self.today }
+                    onclick=link.callback(move |_| Msg::Calendar(CalendarMsg::Play(date)))
+                >
+                    { date.day }
+                </button>
+            </td>
+        }
+    }
+
+    pub fn as_html(&self, link: &ComponentLink<Model>) -> Html {
+        let first = Date {
+            year: self.year,
+            month: self.month,
+            day: 1,
+        };
+        let days = Date::days_in_month(self.year, self.month);
+
+        // Blank cells before the first day line it up under its weekday
+        let cells = (0..first.weekday())
+            .map(|_| None)
+            .chain((1..=days).map(|day| Some(Date { day, ..first })))
+            .collect::<Vec<_>>();
+
+        html! {
+            <div class="controls panel calendar">
+                <div>
+                    <button onclick=link.callback(|_| Msg::Calendar(CalendarMsg::PreviousMonth))>{ "<" }</button>
+                    { format!(" {} {} ", MONTH_NAMES[self.month as usize - 1], self.year) }
+                    <button onclick=link.callback(|_| Msg::Calendar(CalendarMsg::NextMonth))>{ ">" }</button>
+                </div>
+                <table>
+                    <tr>
+                        { for ["S", "M", "T", "W", "T", "F", "S"].iter().map(|d| html! { <th>{ d }</th> }) }
+                    </tr>
+                    { for cells.chunks(7).map(|week| html! {
+                        <tr>
+                            { for week.iter().map(|cell| match cell {
+                                Some(date) => self.day_html(*date, link),
+                                None => html! { <td /> },
+                            })}
+                        </tr>
+                    })}
+                </table>
+                <button onclick=link.callback(|_| Msg::ToggleCalendar)>{ "Close" }</button>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use rand::{prelude::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::card::{Card, Suit, Value, DECK};
+
+pub fn random_seed() -> u64 {
+    rand::random()
+}
+
+// ChaCha8 produces the same numbers on every platform and version, so a seed always gives the
+// same deal.
+pub fn shuffled_deck(seed: u64) -> Vec<Card> {
+    shuffled(DECK.to_vec(), seed)
+}
+
+/// `cards` shuffled in the same way as a single deck, for games that aren't played with one.
+pub fn shuffled(mut cards: Vec<Card>, seed: u64) -> Vec<Card> {
+    cards.shuffle(&mut ChaCha8Rng::seed_from_u64(seed));
+    cards
+}
+
+/// The deck as it was shuffled for deal `number` of the FreeCell game that came with Windows, in
+/// the order the cards are dealt. Deals 1 to 32000 match every other program that uses them.
+pub fn microsoft_deal(number: u64) -> Vec<Card> {
+    const SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+    const VALUES: [Value; 13] = [
+        Value::Ace,
+        Value::Two,
+        Value::Three,
+        Value::Four,
+        Value::Five,
+        Value::Six,
+        Value::Seven,
+        Value::Eight,
+        Value::Nine,
+        Value::Ten,
+        Value::Jack,
+        Value::Queen,
+        Value::King,
+    ];
+
+    // The C runtime's rand(), as the original game called it
+    let mut state = number as u32;
+    let mut rand = move || {
+        state = state.wrapping_mul(214013).wrapping_add(2531011) & 0x7FFF_FFFF;
+        (state >> 16) as usize
+    };
+
+    // Card n is the (n / 4)th value in the (n % 4)th suit, and the deck starts in reverse
+    let mut deck = (0..52).rev().collect::<Vec<usize>>();
+    for i in 0..deck.len() - 1 {
+        let j = deck.len() - 1 - rand() % (deck.len() - i);
+        deck.swap(i, j);
+    }
+
+    deck.into_iter()
+        .map(|n| Card(VALUES[n / 4], SUITS[n % 4]))
+        .collect()
+}
+
+/// How many cards are dealt onto the tableaus at the start of a game.
+pub fn tableau_card_count(columns: usize) -> usize {
+    columns * (columns + 1) / 2
+}
+
+// The column each of the first cards of the deck is dealt to, and whether it lands face up. Cards
+// are dealt as they would be by hand: one card to each column from left to right, then again
+// starting one column further along, with the last card of each column dealt face up.
+pub fn tableau_deal_order(columns: usize) -> impl Iterator<Item = (usize, bool)> {
+    (0..columns)
+        .flat_map(move |round| (round..columns).map(move |column| (column, column == round)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Written the way published deal lists write cards, e.g. "TD" for the ten of diamonds
+    fn short_name(Card(value, suit): Card) -> String {
+        let value = match value {
+            Value::Ten => String::from("T"),
+            _ => value.to_string(),
+        };
+        let suit = match suit {
+            Suit::Spades => 'S',
+            Suit::Clubs => 'C',
+            Suit::Diamonds => 'D',
+            Suit::Hearts => 'H',
+        };
+        format!("{}{}", value, suit)
+    }
+
+    // Deals are published a row at a time, with the first card of each row in the first column
+    fn check_deal(number: u64, layout: &str) {
+        let cards = microsoft_deal(number)
+            .into_iter()
+            .map(short_name)
+            .collect::<Vec<_>>();
+        assert_eq!(cards, layout.split_whitespace().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn first_windows_deal() {
+        check_deal(
+            1,
+            "JD 2D 9H JC 5D 7H 7C 5H
+             KD KC 9S 5S AD QC KH 3H
+             2S KS 9D QD JS AS AH 3C
+             4C 5C TS QH 4H AC 4D 7S
+             3S TD 4S TH 8H 2C JH 7D
+             6D 8S 8D QS 6C 3D 8C TC
+             6S 9C 2H 6H",
+        );
+    }
+
+    #[test]
+    fn windows_deal_617() {
+        check_deal(
+            617,
+            "7D AD 5C 3S 5S 8C 2D AH
+             TD 7S QD AC 6D 8H AS KH
+             TH QC 3H 9D 6S 8D 3D TC
+             KD 5H 9S 3C 8S 7H 4D JS
+             4C QS 9C 9H 7C 6H 2C 2S
+             4S TS 2H 5D JC 6C JH QH
+             JD KS KC 4H",
+        );
+    }
+}
@@ -0,0 +1,59 @@
+use std::{fmt, str::FromStr};
+
+use crate::solver::SolveStats;
+
+// Scores below these are rated easy and medium, and anything above is hard. They split deals
+// into roughly equal bands, with draw three deals tending to score higher.
+const EASY_BELOW: f64 = 7.0;
+const MEDIUM_BELOW: f64 = 13.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    /// Rates a solved deal from how long its solution is, how much searching it took to find,
+    /// and how many cards hide the aces and twos.
+    pub fn rate(stats: &SolveStats) -> Self {
+        let length = stats.solution_length.max(1) as f64;
+        // Positions searched beyond the solution itself are dead ends the player could also
+        // wander into
+        let dead_ends = (stats.nodes as f64 / length).max(1.0).log2();
+        let score = 2.0 * dead_ends + (length - 100.0) / 10.0 + stats.buried_cards as f64 / 2.0;
+
+        if score < EASY_BELOW {
+            Difficulty::Easy
+        } else if score < MEDIUM_BELOW {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "Easy"),
+            Difficulty::Medium => write!(f, "Medium"),
+            Difficulty::Hard => write!(f, "Hard"),
+        }
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|d| d.to_string() == s)
+            .ok_or(())
+    }
+}
@@ -1,30 +1,58 @@
+use std::rc::Rc;
+
+use solitaire_web::{
+    card::{Card, Value},
+    variant::{PileKind, Variant},
+};
 use yew::{html, Html};
 
 use crate::{
-    card::{Card, CardSink, CardSource, CardVisual, PhysicalCard, Value},
+    physical_card::{CardSink, CardSource, CardVisual, PhysicalCard},
     util::Bounds,
-    CardSinks, CardSources, CARD_HEIGHT, CARD_WIDTH,
+    CARD_HEIGHT, CARD_WIDTH,
 };
 
 pub struct Foundation {
-    sink: CardSinks,
-    source: CardSources,
+    variant: Rc<dyn Variant>,
     cards: Vec<PhysicalCard>,
+    // The rank the first card has to be, which isn't always an Ace
+    base: Value,
     x: i32,
     y: i32,
 }
 
 impl Foundation {
-    pub fn new(x: i32, y: i32, sink: CardSinks, source: CardSources) -> Self {
+    pub fn new(x: i32, y: i32, variant: Rc<dyn Variant>, base: Value) -> Self {
         Self {
-            sink,
-            source,
+            variant,
             cards: Vec::new(),
+            base,
             x,
             y,
         }
     }
 
+    pub fn deal_card(&mut self, mut card: PhysicalCard, face_up: bool, animate: bool) {
+        if animate {
+            card.move_to(self.x, self.y);
+        } else {
+            card.set_position(self.x, self.y);
+        }
+        card.set_flipped(!face_up);
+        self.cards.push(card);
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        self.cards.iter().map(PhysicalCard::card).collect()
+    }
+
+    // Puts back a card taken off the top without checking placement rules
+    pub fn restore_card(&mut self, mut physical_card: PhysicalCard) {
+        physical_card.set_position(self.x, self.y);
+        physical_card.set_visible(true);
+        self.cards.push(physical_card);
+    }
+
     pub fn as_html(&self) -> Html {
         let top_card_html = match self.cards.last() {
             Some(card) => card.as_draggable_html(),
@@ -46,29 +74,12 @@ impl Foundation {
 }
 
 impl CardSink for Foundation {
-    fn card_sink(&self) -> CardSinks {
-        self.sink
-    }
-
     fn is_placement_possible(&self, cards: &[Card]) -> bool {
-        if cards.len() == 1 {
-            let card = *cards.first().expect("card should be present");
-            let Card(value, suit) = card;
-
-            if let Some(top_card) = self.cards.last() {
-                let Card(top_value, top_suit) = top_card.card();
-
-                if let Some(next_value) = top_value.next_value() {
-                    top_suit == suit && next_value == value
-                } else {
-                    false
-                }
-            } else {
-                value == Value::Ace
-            }
-        } else {
-            false
-        }
+        self.variant.builds_on_foundation(
+            cards,
+            self.cards.last().map(PhysicalCard::card),
+            self.base,
+        )
     }
 
     // Returns an Err is placement is not possible
@@ -76,7 +87,7 @@ impl CardSink for Foundation {
         &mut self,
         mouse_x: i32,
         mouse_y: i32,
-        mut physical_cards: Vec<PhysicalCard>,
+        physical_cards: Vec<PhysicalCard>,
     ) -> Result<(), ()> {
         if self.is_placement_possible(
             &physical_cards
@@ -84,23 +95,28 @@ impl CardSink for Foundation {
                 .map(PhysicalCard::card)
                 .collect::<Vec<Card>>(),
         ) {
-            // Placement is only possible if there is one card
-            let mut physical_card = physical_cards.pop().expect("card should be present");
-            physical_card.set_xy(self.x, self.y);
-            physical_card.set_prev_loc(
-                mouse_x - CARD_WIDTH as i32 / 2,
-                mouse_y - CARD_HEIGHT as i32 / 2,
-            );
-            physical_card.set_visible(true);
-            self.cards.push(physical_card);
+            // Usually one card, but some variants move a whole suit here at once
+            for mut physical_card in physical_cards {
+                physical_card.set_xy(self.x, self.y);
+                physical_card.set_prev_loc(
+                    mouse_x - CARD_WIDTH as i32 / 2,
+                    mouse_y - CARD_HEIGHT as i32 / 2,
+                );
+                physical_card.set_visible(true);
+                self.cards.push(physical_card);
+            }
             Ok(())
         } else {
             Err(())
         }
     }
 
-    fn within_bounds(&self, x: i32, y: i32) -> bool {
-        Bounds::new(self.x, self.y, CARD_WIDTH, CARD_HEIGHT).contains(x, y)
+    fn bounds(&self) -> Bounds {
+        Bounds::new(self.x, self.y, CARD_WIDTH, CARD_HEIGHT)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cards.is_empty()
     }
 }
 
@@ -121,10 +137,6 @@ impl CardSource for Foundation {
         }
     }
 
-    fn card_source(&self) -> CardSources {
-        self.source
-    }
-
     fn borrow_cards_mut(&mut self, count: usize) -> Vec<&mut PhysicalCard> {
         if count > 0 {
             self.cards.last_mut().map_or_else(Vec::new, |c| vec![c])
@@ -135,7 +147,11 @@ impl CardSource for Foundation {
 
     fn how_many_cards(&self, mouse_x: i32, mouse_y: i32) -> usize {
         if let Some(physical_card) = self.cards.last() {
-            if physical_card.within_bounds(mouse_x, mouse_y) {
+            if physical_card.within_bounds(mouse_x, mouse_y)
+                && self
+                    .variant
+                    .is_movable_group(PileKind::Foundation, &[physical_card.card()])
+            {
                 1
             } else {
                 0
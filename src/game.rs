@@ -0,0 +1,266 @@
+use std::fmt;
+
+use crate::{
+    card::Card,
+    moves::{Move, Pile},
+    options::Draw,
+    record::{GameRecord, RecordError},
+    variant::{
+        Klondike, PileKind, StockRules, Variant, VariantKind, KLONDIKE_FOUNDATIONS,
+        KLONDIKE_TABLEAUS,
+    },
+};
+
+/// A move the rules don't allow in the current position.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IllegalMove(pub Move);
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} cannot be played", self.0)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Column {
+    cards: Vec<Card>,
+    // The first `face_down` cards are face down
+    face_down: usize,
+}
+
+impl Column {
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn face_down(&self) -> usize {
+        self.face_down
+    }
+
+    pub fn face_up(&self) -> &[Card] {
+        &self.cards[self.face_down..]
+    }
+}
+
+/// A game of Klondike without a display, played by the same rules as the web version.
+pub struct Game {
+    seed: u64,
+    // Decides which moves are allowed, so they're the same as on the web
+    variant: Klondike,
+    // The top of the stock and waste are at the end
+    stock: Vec<Card>,
+    waste: Vec<Card>,
+    foundations: [Vec<Card>; KLONDIKE_FOUNDATIONS],
+    tableaus: [Column; KLONDIKE_TABLEAUS],
+    moves: Vec<Move>,
+}
+
+impl Game {
+    pub fn new(seed: u64, draw: Draw) -> Self {
+        Self::deal(Klondike::new(draw), seed)
+    }
+
+    /// Deals a game from `seed`, to be played by `variant`'s rules.
+    pub fn deal(variant: Klondike, seed: u64) -> Self {
+        let deal = variant.deal(seed);
+
+        let mut tableaus: [Column; KLONDIKE_TABLEAUS] = Default::default();
+        for (pile, card, face_up) in deal.cards {
+            let column = match variant.pile_name(pile) {
+                Some(Pile::Tableau(index)) => &mut tableaus[index],
+                _ => unreachable!("Klondike only deals onto the tableaus"),
+            };
+            if !face_up {
+                column.face_down += 1;
+            }
+            column.cards.push(card);
+        }
+
+        Self {
+            seed,
+            variant,
+            stock: deal.stock,
+            waste: Vec::new(),
+            foundations: Default::default(),
+            tableaus,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Deals the recorded game and plays every move in it, checking each against the rules.
+    pub fn from_record(record: &GameRecord) -> Result<Self, RecordError> {
+        if record.variant != VariantKind::Klondike {
+            return Err(RecordError::UnsupportedGame(record.variant));
+        }
+
+        let mut game = Self::new(record.seed, record.draw);
+        for (i, game_move) in record.moves.iter().enumerate() {
+            game.play(*game_move)
+                .map_err(|_| RecordError::IllegalMove(i + 1, *game_move))?;
+        }
+        Ok(game)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn draw(&self) -> Draw {
+        match self.variant.stock_rules() {
+            Some(StockRules::Waste { draw, .. }) => draw,
+            _ => unreachable!("Klondike turns its stock over onto a waste"),
+        }
+    }
+
+    pub fn stock(&self) -> &[Card] {
+        &self.stock
+    }
+
+    pub fn waste(&self) -> &[Card] {
+        &self.waste
+    }
+
+    pub fn foundations(&self) -> &[Vec<Card>; KLONDIKE_FOUNDATIONS] {
+        &self.foundations
+    }
+
+    pub fn tableaus(&self) -> &[Column; KLONDIKE_TABLEAUS] {
+        &self.tableaus
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn record(&self) -> GameRecord {
+        GameRecord {
+            variant: VariantKind::Klondike,
+            seed: self.seed,
+            draw: self.draw(),
+            moves: self.moves.clone(),
+        }
+    }
+
+    pub fn is_won(&self) -> bool {
+        // In the order Klondike lists its piles
+        let piles = self
+            .foundations
+            .iter()
+            .cloned()
+            .chain(self.tableaus.iter().map(|column| column.cards.clone()))
+            .chain(std::iter::once(
+                [self.stock.as_slice(), &self.waste].concat(),
+            ))
+            .collect::<Vec<_>>();
+        self.variant.is_won(&piles)
+    }
+
+    /// Turns over cards from the stock, or turns the waste back over once the stock is empty.
+    /// Returns the move made, or None when both are empty.
+    pub fn deal_from_stock(&mut self) -> Option<Move> {
+        let game_move = if !self.stock.is_empty() {
+            Move::Draw
+        } else if !self.waste.is_empty() {
+            Move::Recycle
+        } else {
+            return None;
+        };
+
+        self.play(game_move).ok().map(|_| game_move)
+    }
+
+    // The kind of pile `pile` is, as Klondike lays them out
+    fn kind(&self, pile: Pile) -> Option<PileKind> {
+        let index = self.variant.pile_index(pile)?;
+        self.variant.piles().get(index).map(|spec| spec.kind)
+    }
+
+    // The cards in `pile` that are face up, which for the stock means its waste
+    fn face_up(&self, pile: Pile) -> Option<&[Card]> {
+        match pile {
+            Pile::Waste => Some(&self.waste),
+            Pile::Foundation(index) => self.foundations.get(index).map(Vec::as_slice),
+            Pile::Tableau(index) => self.tableaus.get(index).map(Column::face_up),
+            Pile::Cell(_) | Pile::Pyramid(_) | Pile::Reserve => None,
+        }
+    }
+
+    // The cards that would be moved, if `from` has that many that can be picked up
+    fn peek(&self, from: Pile, count: usize) -> Option<&[Card]> {
+        let kind = self.kind(from)?;
+        let cards = self.face_up(from)?;
+        // Only runs on a tableau can be picked up more than a card at a time
+        let single_card_only = kind != PileKind::Tableau;
+
+        if count == 0 || count > cards.len() || (single_card_only && count > 1) {
+            return None;
+        }
+        let cards = &cards[cards.len() - count..];
+        Some(cards).filter(|cards| self.variant.is_movable_group(kind, cards))
+    }
+
+    fn accepts(&self, to: Pile, cards: &[Card]) -> bool {
+        let top = self.face_up(to).and_then(|cards| cards.last().copied());
+        self.kind(to)
+            .is_some_and(|kind| self.variant.builds_on(kind, cards, top))
+    }
+
+    /// Plays a move if the rules allow it.
+    pub fn play(&mut self, game_move: Move) -> Result<(), IllegalMove> {
+        let illegal = IllegalMove(game_move);
+        let (draw, recycle) = match self.variant.stock_rules() {
+            Some(StockRules::Waste { draw, recycle }) => (draw, recycle),
+            _ => unreachable!("Klondike turns its stock over onto a waste"),
+        };
+        match game_move {
+            Move::Draw if !self.stock.is_empty() => {
+                for _ in 0..draw.count() {
+                    if let Some(card) = self.stock.pop() {
+                        self.waste.push(card);
+                    }
+                }
+            }
+            Move::Recycle if recycle && self.stock.is_empty() && !self.waste.is_empty() => {
+                self.stock = self.waste.drain(..).rev().collect();
+            }
+            Move::Transfer { from, count, to } if from != to => {
+                let cards = self.peek(from, count).ok_or(illegal)?;
+                if !self.accepts(to, cards) {
+                    return Err(illegal);
+                }
+
+                let cards = match from {
+                    Pile::Waste => self.waste.split_off(self.waste.len() - count),
+                    Pile::Foundation(index) => {
+                        let foundation = &mut self.foundations[index];
+                        foundation.split_off(foundation.len() - count)
+                    }
+                    Pile::Tableau(index) => {
+                        let column = &mut self.tableaus[index];
+                        let cards = column.cards.split_off(column.cards.len() - count);
+                        // The card underneath is turned over once it is uncovered
+                        if column.face_down > 0 && column.face_down == column.cards.len() {
+                            column.face_down -= 1;
+                        }
+                        cards
+                    }
+                    Pile::Cell(_) | Pile::Pyramid(_) | Pile::Reserve => {
+                        unreachable!("Klondike has no cells, pyramid or reserve")
+                    }
+                };
+
+                match to {
+                    Pile::Foundation(index) => self.foundations[index].extend(cards),
+                    Pile::Tableau(index) => self.tableaus[index].cards.extend(cards),
+                    Pile::Waste | Pile::Cell(_) | Pile::Pyramid(_) | Pile::Reserve => {
+                        unreachable!("the waste never accepts cards, and there are no other piles")
+                    }
+                }
+            }
+            _ => return Err(illegal),
+        }
+
+        self.moves.push(game_move);
+        Ok(())
+    }
+}
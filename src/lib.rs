@@ -0,0 +1,14 @@
+// The parts of the game that don't depend on the browser, shared by every frontend
+pub mod card;
+pub mod deal;
+pub mod difficulty;
+pub mod game;
+pub mod moves;
+pub mod options;
+pub mod record;
+pub mod rules;
+pub mod score;
+pub mod share;
+pub mod solver;
+pub mod variant;
+pub mod winnable;
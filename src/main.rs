@@ -1,18 +1,50 @@
+use std::{cmp::Reverse, collections::VecDeque, rc::Rc, time::Duration};
+
+use cascade::Cascade;
 use foundation::Foundation;
-use rand::{prelude::SliceRandom, thread_rng};
+use solitaire_web::{
+    card::Card,
+    difficulty::Difficulty,
+    moves::Move,
+    options::{Draw, Options},
+    record::{GameRecord, RecordError},
+    score::StreakScore,
+    share,
+    solver::{OpenSolver, SolveResult},
+    variant::{PileKind, PileSpec, StockRules, Variant, VariantKind},
+    winnable::SeedSearch,
+};
 use tableau::Tableau;
 use wasm_bindgen::{closure::Closure, JsCast};
-use web_sys::{Document, PointerEvent, TouchEvent};
-use yew::{html, Component, ComponentLink, Html, ShouldRender};
+use web_sys::{Document, Element, PointerEvent};
+use yew::{
+    html,
+    services::{interval::IntervalTask, render::RenderTask, IntervalService, RenderService},
+    ChangeData, Component, ComponentLink, Html, InputData, ShouldRender,
+};
 
-mod card;
+mod card_slot;
+mod cascade;
+mod daily;
 mod foundation;
+mod physical_card;
+mod pile;
+mod replay;
+mod reserve;
+mod stats;
 mod stock_discard;
 mod tableau;
 mod util;
 
-use card::{Card, CardSink, CardSource, CardVisual, PhysicalCard, DECK};
+use card_slot::CardSlot;
+use daily::{Calendar, CalendarMsg, DailyResult, Date};
+use physical_card::{CardSink, CardSource, CardVisual, DropHighlight, PhysicalCard};
+use pile::BoardPile;
+use replay::{Replay, ReplayMsg};
+use reserve::Reserve;
+use stats::VariantStats;
 use stock_discard::StockDiscard;
+use util::Bounds;
 
 const CARD_WIDTH: u32 = 125;
 const CARD_HEIGHT: u32 = 175;
@@ -25,58 +57,74 @@ const STACKED_CARD_Y_STRIDE: i32 = 45;
 const CARD_X_STRIDE: i32 = CARD_WIDTH as i32 + 20;
 const CARD_Y_STRIDE: i32 = CARD_HEIGHT as i32 + 20;
 
-const STOCK_DISCARD_X: i32 = PADDING;
-const STOCK_DISCARD_Y: i32 = PADDING;
+// How far the pointer has to move while pressed before it counts as a drag rather than a click
+const DRAG_THRESHOLD: i32 = 5;
 
-const FOUNDATIONS_X: i32 = PADDING + 3 * CARD_X_STRIDE;
-const FOUNDATIONS_Y: i32 = PADDING;
+// Time between each card leaving the stock during the opening deal
+const DEAL_INTERVAL_MS: u64 = 60;
 
-const TABLEAUS_Y: i32 = PADDING + CARD_Y_STRIDE;
-const TABLEAUS_X: i32 = PADDING;
+// The search for a winnable deal runs in small steps, so the page keeps responding while it runs
+const SEARCH_INTERVAL_MS: u64 = 10;
+const SEARCH_STEP_NODES: usize = 1000;
+// How long to search before falling back to a deal known to be winnable
+const SEARCH_TIME_BUDGET_MS: f64 = 3000.0;
+// Daily deals get longer, since a deal from the table isn't the one everyone else was searching
+// for
+const DAILY_SEARCH_TIME_BUDGET_MS: f64 = 10000.0;
 
 #[allow(clippy::enum_variant_names)]
 enum Msg {
     MouseUp(i32, i32),
     MouseDown(i32, i32),
     MouseMove(i32, i32),
-    Touch(i32, i32),
+    DealTick,
+    SearchTick,
+    VerdictTick,
+    CascadeFrame(f64),
+    NewGame,
+    ToggleOneClickMoves,
+    ToggleDrawOne,
+    ToggleWinnableOnly,
+    SetDifficulty(Option<Difficulty>),
+    SetVariant(VariantKind),
+    ToggleExport,
+    ToggleImport,
+    ToggleShare,
+    ToggleStats,
+    DismissNotice,
+    ImportInput(String),
+    LoadRecord,
+    Replay(ReplayMsg),
+    ToggleCalendar,
+    Calendar(CalendarMsg),
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum CardSources {
-    Discard,
-    Foundation1,
-    Foundation2,
-    Foundation3,
-    Foundation4,
-    Tableau1,
-    Tableau2,
-    Tableau3,
-    Tableau4,
-    Tableau5,
-    Tableau6,
-    Tableau7,
+// Face-up cards picked out from a source, either to drag or as the click-to-move selection
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Selection {
+    // The index of the pile the cards are in
+    source: usize,
+    count: usize,
 }
 
+// Where the pointer went down, which becomes a click if it's released without being dragged
 #[derive(Clone, Copy, Debug)]
-pub enum CardSinks {
-    Foundation1,
-    Foundation2,
-    Foundation3,
-    Foundation4,
-    Tableau1,
-    Tableau2,
-    Tableau3,
-    Tableau4,
-    Tableau5,
-    Tableau6,
-    Tableau7,
+struct Press {
+    x: i32,
+    y: i32,
+    cards: Option<Selection>,
+}
+
+impl Press {
+    fn is_drag(&self, x: i32, y: i32) -> bool {
+        (x - self.x).abs() > DRAG_THRESHOLD || (y - self.y).abs() > DRAG_THRESHOLD
+    }
 }
 
 #[derive(Clone)]
 struct HeldCard {
     cards: Vec<Card>,
-    source: CardSources,
+    source: usize,
     x: i32,
     y: i32,
     count: usize,
@@ -86,7 +134,7 @@ struct HeldCard {
 impl HeldCard {
     pub fn new(
         cards: Vec<Card>,
-        source: CardSources,
+        source: usize,
         count: usize,
         x: i32,
         y: i32,
@@ -102,7 +150,7 @@ impl HeldCard {
             prev_pos: Some((x, y)),
         }
     }
-    pub fn source(&self) -> CardSources {
+    pub fn source(&self) -> usize {
         self.source
     }
 
@@ -110,6 +158,15 @@ impl HeldCard {
         self.count
     }
 
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    // The area covered by the first held card, which is the one that lands on a sink
+    pub fn bounds(&self) -> Bounds {
+        Bounds::new(self.x, self.y, CARD_WIDTH, CARD_HEIGHT)
+    }
+
     pub fn set_mouse_position(&mut self, x: i32, y: i32) {
         self.set_position(x - CARD_WIDTH as i32 / 2, y - CARD_HEIGHT as i32 / 2);
     }
@@ -143,91 +200,1047 @@ impl HeldCard {
 
 struct Model {
     link: ComponentLink<Self>,
-    stock_discard: StockDiscard,
-    foundation1: Foundation,
-    foundation2: Foundation,
-    foundation3: Foundation,
-    foundation4: Foundation,
-    tableau1: Tableau,
-    tableau2: Tableau,
-    tableau3: Tableau,
-    tableau4: Tableau,
-    tableau5: Tableau,
-    tableau6: Tableau,
-    tableau7: Tableau,
+    variant_kind: VariantKind,
+    variant: Rc<dyn Variant>,
+    // In the order the variant lists them
+    piles: Vec<BoardPile>,
     held_card: Option<HeldCard>,
+    press: Option<Press>,
+    selection: Option<Selection>,
+    options: Options,
+    seed: u64,
+    draw: Draw,
+    // How hard the solver rated the deal, if it was dealt from a search
+    rating: Option<Difficulty>,
+    // Every move made so far this game, in order
+    moves: Vec<Move>,
+    show_export: bool,
+    show_import: bool,
+    import_text: String,
+    import_error: Option<String>,
+    replay: Option<Replay>,
+    show_share: bool,
+    show_stats: bool,
+    // Whether this game has been counted towards the statistics as played, and as won
+    counted_played: bool,
+    counted_won: bool,
+    // A message for the player, such as why a link couldn't be opened
+    notice: Option<String>,
+    // The date of the daily deal being played, if this game is one
+    daily: Option<Date>,
+    calendar: Option<Calendar>,
+    // When the game was dealt, in milliseconds since the epoch
+    started_at: f64,
+    // The search for the next deal, and the daily deal it is for if it is one
+    seed_search: Option<SeedSearch>,
+    search_daily: Option<Date>,
+    search_task: Option<IntervalTask>,
+    // Whether the deal can be won, for games with every card visible from the start. The search
+    // runs a little at a time like the one for winnable deals.
+    verdict_search: Option<OpenSolver>,
+    verdict: Option<SolveResult>,
+    verdict_task: Option<IntervalTask>,
+    // Cards still to be dealt, as (pile index, card, face up)
+    deal_queue: VecDeque<(usize, PhysicalCard, bool)>,
+    deal_task: Option<IntervalTask>,
+    cascade: Option<Cascade>,
+    cascade_task: Option<RenderTask>,
+    next_cascade_foundation: usize,
 }
 
 impl Model {
-    fn borrow_sources(&mut self) -> Vec<&mut dyn CardSource> {
-        vec![
-            self.stock_discard.discard_mut(),
-            &mut self.foundation1,
-            &mut self.foundation2,
-            &mut self.foundation3,
-            &mut self.foundation4,
-            &mut self.tableau1,
-            &mut self.tableau2,
-            &mut self.tableau3,
-            &mut self.tableau4,
-            &mut self.tableau5,
-            &mut self.tableau6,
-            &mut self.tableau7,
-        ]
-    }
-
-    fn borrow_sinks(&mut self) -> Vec<&mut dyn CardSink> {
-        vec![
-            &mut self.foundation1,
-            &mut self.foundation2,
-            &mut self.foundation3,
-            &mut self.foundation4,
-            &mut self.tableau1,
-            &mut self.tableau2,
-            &mut self.tableau3,
-            &mut self.tableau4,
-            &mut self.tableau5,
-            &mut self.tableau6,
-            &mut self.tableau7,
-        ]
-    }
-
-    fn borrow_source(&mut self, source: CardSources) -> &mut dyn CardSource {
-        match source {
-            CardSources::Discard => self.stock_discard.discard_mut(),
-            CardSources::Foundation1 => &mut self.foundation1,
-            CardSources::Foundation2 => &mut self.foundation2,
-            CardSources::Foundation3 => &mut self.foundation3,
-            CardSources::Foundation4 => &mut self.foundation4,
-            CardSources::Tableau1 => &mut self.tableau1,
-            CardSources::Tableau2 => &mut self.tableau2,
-            CardSources::Tableau3 => &mut self.tableau3,
-            CardSources::Tableau4 => &mut self.tableau4,
-            CardSources::Tableau5 => &mut self.tableau5,
-            CardSources::Tableau6 => &mut self.tableau6,
-            CardSources::Tableau7 => &mut self.tableau7,
-        }
-    }
-
-    fn borrow_sink(&mut self, sink: CardSinks) -> &mut dyn CardSink {
-        match sink {
-            CardSinks::Foundation1 => &mut self.foundation1,
-            CardSinks::Foundation2 => &mut self.foundation2,
-            CardSinks::Foundation3 => &mut self.foundation3,
-            CardSinks::Foundation4 => &mut self.foundation4,
-            CardSinks::Tableau1 => &mut self.tableau1,
-            CardSinks::Tableau2 => &mut self.tableau2,
-            CardSinks::Tableau3 => &mut self.tableau3,
-            CardSinks::Tableau4 => &mut self.tableau4,
-            CardSinks::Tableau5 => &mut self.tableau5,
-            CardSinks::Tableau6 => &mut self.tableau6,
-            CardSinks::Tableau7 => &mut self.tableau7,
-        }
-    }
-
-    #[allow(dead_code)]
-    fn borrow_held_source(&mut self, held_card: HeldCard) -> &mut dyn CardSource {
-        self.borrow_source(held_card.source())
+    // Piles that are covered by others can't be played to or from, so they're left out
+    fn sources(&self) -> impl Iterator<Item = (usize, &dyn CardSource)> {
+        self.piles
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| self.is_uncovered(*i))
+            .filter_map(|(i, pile)| pile.source().map(|source| (i, source)))
+    }
+
+    fn sinks(&self) -> impl Iterator<Item = (usize, &dyn CardSink)> {
+        self.piles
+            .iter()
+            .enumerate()
+            .filter(move |(i, _)| self.is_uncovered(*i))
+            .filter_map(|(i, pile)| pile.sink().map(|sink| (i, sink)))
+    }
+
+    fn is_uncovered(&self, index: usize) -> bool {
+        pile::is_uncovered(self.variant.as_ref(), &self.piles, index)
+    }
+
+    fn can_pick_up(&self, source: usize, count: usize) -> bool {
+        pile::can_pick_up(self.variant.as_ref(), &self.piles, source, count)
+    }
+
+    fn is_sink(&self, index: usize) -> bool {
+        self.piles[index].sink().is_some()
+    }
+
+    fn borrow_source(&mut self, index: usize) -> &mut dyn CardSource {
+        self.piles[index]
+            .source_mut()
+            .expect("pile should be a card source")
+    }
+
+    fn borrow_sink(&mut self, index: usize) -> &mut dyn CardSink {
+        self.piles[index]
+            .sink_mut()
+            .expect("pile should be a card sink")
+    }
+
+    fn stock_discard_mut(&mut self) -> Option<&mut StockDiscard> {
+        self.piles.iter_mut().find_map(|pile| match pile {
+            BoardPile::Stock(stock_discard) => Some(stock_discard),
+            _ => None,
+        })
+    }
+
+    fn foundations(&self) -> impl Iterator<Item = (usize, &Foundation)> {
+        self.piles
+            .iter()
+            .enumerate()
+            .filter_map(|(i, pile)| match pile {
+                BoardPile::Foundation(foundation) => Some((i, foundation)),
+                _ => None,
+            })
+    }
+
+    fn is_dealing(&self) -> bool {
+        !self.deal_queue.is_empty()
+    }
+
+    fn deal_next_card(&mut self, animate: bool) {
+        if let Some((pile, card, face_up)) = self.deal_queue.pop_front() {
+            self.piles[pile].deal_card(card, face_up, animate);
+        }
+
+        if self.deal_queue.is_empty() {
+            self.deal_task = None;
+        }
+    }
+
+    fn finish_deal(&mut self) {
+        while self.is_dealing() {
+            self.deal_next_card(false);
+        }
+    }
+
+    // Finds the sink the held cards overlap the most, ignoring the pile they came from. When
+    // `legal_only` is set, sinks the cards can't be placed onto are skipped.
+    fn overlapped_sink(&self, held_card: &HeldCard, legal_only: bool) -> Option<usize> {
+        let held_bounds = held_card.bounds();
+
+        self.sinks()
+            .filter(|(i, _)| *i != held_card.source())
+            .filter(|(i, _)| {
+                !legal_only || self.can_place(held_card.source(), held_card.cards(), *i)
+            })
+            .map(|(i, sink)| (held_bounds.overlap_area(&sink.bounds()), i))
+            .filter(|(area, _)| *area > 0)
+            // Ties go to the sink listed first
+            .max_by_key(|(area, i)| (*area, Reverse(*i)))
+            .map(|(_, i)| i)
+    }
+
+    fn drop_target(&self, held_card: &HeldCard) -> Option<usize> {
+        self.overlapped_sink(held_card, true)
+    }
+
+    fn drop_highlights_html(&self, held_card: &HeldCard) -> Html {
+        let target = self
+            .drop_target(held_card)
+            .or_else(|| self.overlapped_sink(held_card, false));
+
+        html! {
+            { for self.sinks().filter_map(|(i, sink)| {
+                // Dropping cards back where they came from just returns them
+                if held_card.source() == i {
+                    return None;
+                }
+
+                let legal = self.can_place(held_card.source(), held_card.cards(), i);
+                let highlight = match (target == Some(i), legal) {
+                    (true, true) => DropHighlight::Accepted,
+                    (true, false) => DropHighlight::Rejected,
+                    (false, true) => DropHighlight::Legal,
+                    (false, false) => return None,
+                };
+                Some(sink.highlight_html(highlight))
+            }) }
+        }
+    }
+
+    // How many cards can be moved onto the sink at once, which some variants limit by how many
+    // empty piles there are to move them through
+    fn move_capacity(&self, sink: usize) -> usize {
+        let empty_piles = |kind| {
+            self.piles
+                .iter()
+                .enumerate()
+                .filter(|(i, pile)| *i != sink && pile.kind() == kind)
+                .filter(|(_, pile)| pile.sink().is_some_and(|s| s.is_empty()))
+                .count()
+        };
+
+        self.variant.move_capacity(
+            self.piles[sink].kind(),
+            empty_piles(PileKind::Cell),
+            empty_piles(PileKind::Tableau),
+        )
+    }
+
+    fn can_place(&self, source: usize, cards: &[Card], sink: usize) -> bool {
+        cards.len() <= self.move_capacity(sink)
+            && self.can_pick_up(source, cards.len())
+            && self
+                .variant
+                .is_movable_group(self.piles[source].kind(), cards)
+            && self.is_uncovered(sink)
+            && (self.piles[sink]
+                .sink()
+                .is_some_and(|s| s.is_placement_possible(cards))
+                || self.pairs_with(cards, sink))
+    }
+
+    // Whether `cards` can be paired with the sink's top card, taking both off the board. The top
+    // card has to be one that could be picked up itself.
+    fn pairs_with(&self, cards: &[Card], sink: usize) -> bool {
+        let top = self.piles[sink]
+            .source()
+            .and_then(|s| s.peek_cards(1).pop())
+            .filter(|top| {
+                self.variant
+                    .is_movable_group(self.piles[sink].kind(), &[*top])
+            });
+        match (cards, top) {
+            ([card], Some(top)) => self.variant.pairs_with(*card, top),
+            _ => false,
+        }
+    }
+
+    fn cards_at(&self, x: i32, y: i32) -> Option<Selection> {
+        self.sources().find_map(|(i, source)| {
+            let count = source.how_many_cards(x, y);
+            if count == 0 {
+                None
+            } else {
+                Some(Selection { source: i, count })
+            }
+        })
+    }
+
+    fn sink_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.sinks()
+            .find(|(_, s)| s.bounds().contains(x, y))
+            .map(|(i, _)| i)
+    }
+
+    fn start_drag(&mut self, selection: Selection, mouse_x: i32, mouse_y: i32) {
+        let source = self.borrow_source(selection.source);
+        source
+            .borrow_cards_mut(selection.count)
+            .iter_mut()
+            .for_each(|c| c.set_visible(false));
+        let cards = source.peek_cards(selection.count);
+
+        let (x, y) = source
+            .borrow_cards(selection.count)
+            .first()
+            .expect("card should be present")
+            .position();
+        self.held_card = Some(HeldCard::new(
+            cards,
+            selection.source,
+            selection.count,
+            x,
+            y,
+            mouse_x,
+            mouse_y,
+        ));
+    }
+
+    // Moves the cards onto the sink if the rules allow it, returning whether they were moved
+    fn move_cards(
+        &mut self,
+        selection: Selection,
+        sink: usize,
+        mouse_x: i32,
+        mouse_y: i32,
+    ) -> bool {
+        let cards = self
+            .borrow_source(selection.source)
+            .peek_cards(selection.count);
+        if !self.can_place(selection.source, &cards, sink) {
+            return false;
+        }
+        let paired = !self.piles[sink]
+            .sink()
+            .is_some_and(|s| s.is_placement_possible(&cards));
+
+        let mut physical_cards = self
+            .borrow_source(selection.source)
+            .take_cards(selection.count);
+        let pile_name = |index| {
+            self.variant
+                .pile_name(index)
+                .expect("every pile should have a name")
+        };
+        self.moves.push(Move::Transfer {
+            from: pile_name(selection.source),
+            count: physical_cards.len(),
+            to: pile_name(sink),
+        });
+
+        // Paired cards go to the foundation together with the card they were played onto
+        let sink = if paired {
+            physical_cards.extend(self.borrow_source(sink).take_cards(1));
+            self.foundations()
+                .map(|(i, _)| i)
+                .next()
+                .expect("a variant with pairs should have a foundation")
+        } else {
+            sink
+        };
+        self.borrow_sink(sink)
+            .place_cards(mouse_x, mouse_y, physical_cards)
+            .expect("placement should be possible");
+        self.reveal_uncovered_cards();
+        self.clear_completed_runs();
+        true
+    }
+
+    // Turns over face-down cards that nothing covers any more
+    fn reveal_uncovered_cards(&mut self) {
+        for i in 0..self.piles.len() {
+            if self.is_uncovered(i) {
+                if let BoardPile::Pyramid(slot) = &mut self.piles[i] {
+                    slot.turn_face_up();
+                }
+            }
+        }
+    }
+
+    fn pile_indices(&self, kind: PileKind) -> Vec<usize> {
+        self.piles
+            .iter()
+            .enumerate()
+            .filter(|(_, pile)| pile.kind() == kind)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    // Sends any runs the variant takes off the tableaus by itself to the first empty foundation.
+    // This isn't recorded as a move, as it happens again whenever the moves are played back.
+    fn clear_completed_runs(&mut self) {
+        for tableau in self.pile_indices(PileKind::Tableau) {
+            let cards = self.borrow_source(tableau).peek_cards(usize::MAX);
+            let count = match self.variant.completed_run(&cards) {
+                Some(count) => count,
+                None => continue,
+            };
+            let sink = match self.foundations().find(|(_, f)| f.is_empty()) {
+                Some((sink, _)) => sink,
+                None => return,
+            };
+
+            let physical_cards = self.borrow_source(tableau).take_cards(count);
+            let (x, y) = physical_cards
+                .first()
+                .expect("card should be present")
+                .position();
+            self.borrow_sink(sink)
+                .place_cards(
+                    x + CARD_WIDTH as i32 / 2,
+                    y + CARD_HEIGHT as i32 / 2,
+                    physical_cards,
+                )
+                .expect("a completed run should go on an empty foundation");
+        }
+    }
+
+    // Turns the stock over onto the waste, or deals a card from it onto every tableau, depending
+    // on the variant. Returns the move made, or None if the stock can't be dealt from.
+    fn deal_from_stock(&mut self) -> Option<Move> {
+        match self.variant.stock_rules()? {
+            StockRules::Waste { .. } => self.stock_discard_mut()?.deal_into_discard(),
+            StockRules::Tableaus { allow_empty } => {
+                let tableaus = self.pile_indices(PileKind::Tableau);
+                if !allow_empty
+                    && tableaus
+                        .iter()
+                        .any(|i| self.piles[*i].sink().is_some_and(|s| s.is_empty()))
+                {
+                    return None;
+                }
+
+                let cards = self.stock_discard_mut()?.take_from_stock(tableaus.len());
+                if cards.is_empty() {
+                    return None;
+                }
+                for (tableau, card) in tableaus.into_iter().zip(cards) {
+                    self.piles[tableau].deal_card(card, true, true);
+                }
+                self.clear_completed_runs();
+                Some(Move::Draw)
+            }
+        }
+    }
+
+    // Like `move_cards`, but the cards travel from where they are rather than from the pointer
+    fn move_selection(&mut self, selection: Selection, sink: usize) -> bool {
+        let position = self
+            .borrow_source(selection.source)
+            .borrow_cards(selection.count)
+            .first()
+            .map(|c| c.position());
+
+        position.is_some_and(|(x, y)| {
+            self.move_cards(
+                selection,
+                sink,
+                x + CARD_WIDTH as i32 / 2,
+                y + CARD_HEIGHT as i32 / 2,
+            )
+        })
+    }
+
+    // Picks where the cards are most useful: a foundation, then a tableau if the move turns over
+    // a face-down card, then a tableau with cards on it, and finally an empty tableau. Ties go to
+    // the sink listed first.
+    fn best_target(&self, selection: Selection) -> Option<usize> {
+        let source_kind = self.piles[selection.source].kind();
+        let source = self.piles[selection.source]
+            .source()
+            .expect("pile should be a card source");
+        let cards = source.peek_cards(selection.count);
+        let card_below = source.card_below(selection.count);
+        let reveals_card = card_below.is_some_and(|c| c.flipped());
+        // Moving everything off a tableau or cell into another empty one achieves nothing
+        let empties_source =
+            matches!(source_kind, PileKind::Tableau | PileKind::Cell) && card_below.is_none();
+
+        self.sinks()
+            .filter(|(i, _)| *i != selection.source)
+            .filter(|(i, _)| self.can_place(selection.source, &cards, *i))
+            .filter_map(|(i, sink)| {
+                let priority = if self.piles[i].kind() == PileKind::Foundation {
+                    0
+                } else if reveals_card {
+                    1
+                } else if !sink.is_empty() {
+                    2
+                } else if !empties_source || self.piles[i].kind() != source_kind {
+                    3
+                } else {
+                    return None;
+                };
+                Some((priority, i))
+            })
+            .min_by_key(|(priority, i)| (*priority, *i))
+            .map(|(_, i)| i)
+    }
+
+    fn click(&mut self, press: Press) -> bool {
+        match (self.selection.take(), press.cards) {
+            (Some(selected), Some(clicked)) if selected == clicked => {
+                // Clicking the selection again sends it wherever it can go
+                if let Some(sink) = self.best_target(selected) {
+                    self.move_selection(selected, sink);
+                }
+                true
+            }
+            (Some(selected), clicked) => {
+                let target = clicked
+                    .map(|c| c.source)
+                    .filter(|source| self.is_sink(*source))
+                    .or_else(|| self.sink_at(press.x, press.y));
+                let moved = target.is_some_and(|sink| self.move_selection(selected, sink));
+
+                if !moved {
+                    self.selection = clicked;
+                }
+                true
+            }
+            (None, Some(clicked)) if self.options.one_click_moves => {
+                match self.best_target(clicked) {
+                    Some(sink) => {
+                        self.move_selection(clicked, sink);
+                    }
+                    None => self.selection = Some(clicked),
+                }
+                true
+            }
+            (None, clicked) => {
+                self.selection = clicked;
+                clicked.is_some()
+            }
+        }
+    }
+
+    // Plays a move from a game record, checking it against the same rules as live play
+    fn apply_move(&mut self, game_move: Move) -> Result<(), ()> {
+        match game_move {
+            Move::Draw | Move::Recycle => {
+                let stock_move = self.deal_from_stock();
+                if stock_move == Some(game_move) {
+                    self.moves.push(game_move);
+                    Ok(())
+                } else {
+                    Err(())
+                }
+            }
+            Move::Transfer { from, count, to } => {
+                let source = self.variant.pile_index(from).ok_or(())?;
+                let sink = self
+                    .variant
+                    .pile_index(to)
+                    .filter(|sink| self.is_sink(*sink))
+                    .ok_or(())?;
+                let selection = Selection { source, count };
+
+                // A record can name a card a player couldn't have picked up
+                if self.can_pick_up(source, count) && self.move_selection(selection, sink) {
+                    Ok(())
+                } else {
+                    Err(())
+                }
+            }
+        }
+    }
+
+    // Deals the recorded game and plays its first `move_count` moves
+    fn from_record(
+        link: ComponentLink<Self>,
+        options: Options,
+        record: &GameRecord,
+        move_count: usize,
+    ) -> Result<Self, RecordError> {
+        let mut model = Self::new_game(link, options, record.variant, record.seed, record.draw);
+        model.finish_deal();
+
+        for (i, game_move) in record.moves.iter().take(move_count).enumerate() {
+            model
+                .apply_move(*game_move)
+                .map_err(|_| RecordError::IllegalMove(i + 1, *game_move))?;
+        }
+
+        Ok(model)
+    }
+
+    fn load_record(&mut self) -> Result<(), RecordError> {
+        let record = self.import_text.parse::<GameRecord>()?;
+        // Playing every move up front means a bad record is rejected before the viewer opens
+        Self::from_record(self.link.clone(), self.options, &record, record.moves.len())?;
+
+        *self = Self::from_record(self.link.clone(), self.options, &record, 0)?;
+        self.replay = Some(Replay::new(record));
+        Ok(())
+    }
+
+    fn show_replay_position(&mut self, position: usize) {
+        let mut replay = match self.replay.take() {
+            Some(replay) => replay,
+            None => return,
+        };
+        let position = position.min(replay.len());
+
+        if position == replay.position() + 1 {
+            let game_move = replay.record().moves[replay.position()];
+            self.apply_move(game_move)
+                .expect("record should have been checked when loaded");
+        } else if position != replay.position() {
+            // Going backwards, or skipping ahead, replays the game from the deal
+            *self = Self::from_record(self.link.clone(), self.options, replay.record(), position)
+                .expect("record should have been checked when loaded");
+        }
+
+        replay.set_position(position);
+        self.replay = Some(replay);
+    }
+
+    fn update_replay(&mut self, msg: ReplayMsg) -> ShouldRender {
+        let (position, len) = match &self.replay {
+            Some(replay) => (replay.position(), replay.len()),
+            None => return false,
+        };
+
+        match msg {
+            ReplayMsg::Start => self.show_replay_position(0),
+            ReplayMsg::Back => self.show_replay_position(position.saturating_sub(1)),
+            ReplayMsg::Forward => self.show_replay_position(position + 1),
+            ReplayMsg::End => self.show_replay_position(len),
+            ReplayMsg::Jump(position) => self.show_replay_position(position),
+            ReplayMsg::Tick => {
+                self.show_replay_position(position + 1);
+                if position + 1 >= len {
+                    if let Some(replay) = &mut self.replay {
+                        replay.pause();
+                    }
+                }
+            }
+            ReplayMsg::TogglePlay => {
+                if position >= len {
+                    self.show_replay_position(0);
+                }
+                let link = self.link.clone();
+                if let Some(replay) = &mut self.replay {
+                    if replay.is_playing() {
+                        replay.pause();
+                    } else {
+                        replay.play(&link);
+                    }
+                }
+            }
+            ReplayMsg::SetSpeed(speed) => {
+                let link = self.link.clone();
+                if let Some(replay) = &mut self.replay {
+                    replay.set_speed(speed, &link);
+                }
+            }
+            // Play carries on from the position being viewed
+            ReplayMsg::Exit => self.replay = None,
+        }
+        true
+    }
+
+    fn import_html(&self) -> Html {
+        let error_html = self.import_error.as_ref().map_or(
+            html! {},
+            |error| html! { <div class="error">{ error }</div> },
+        );
+
+        html! {
+            <div class="controls panel">
+                <textarea
+                    rows=8
+                    cols=40
+                    placeholder="Paste an exported game here"
+                    value=self.import_text.clone()
+                    oninput=self.link.callback(|e: InputData| Msg::ImportInput(e.value))
+                />
+                { error_html }
+                <button onclick=self.link.callback(|_| Msg::LoadRecord)>{ "Load" }</button>
+                <button onclick=self.link.callback(|_| Msg::ToggleImport)>{ "Close" }</button>
+            </div>
+        }
+    }
+
+    fn start_cascade_if_won(&mut self) {
+        if self.cascade.is_none() && self.is_won() {
+            self.start_cascade();
+        }
+    }
+
+    fn selection_html(&self) -> Html {
+        let cards = self.selection.and_then(|selection| {
+            self.piles[selection.source]
+                .source()
+                .map(|s| s.borrow_cards(selection.count))
+        });
+
+        match cards.as_deref() {
+            Some([first, .., last]) | Some([first @ last]) => {
+                let ((x, y), (last_x, last_y)) = (first.position(), last.position());
+                let bounds = Bounds::new(
+                    x,
+                    y,
+                    (last_x - x) as u32 + CARD_WIDTH,
+                    (last_y - y) as u32 + CARD_HEIGHT,
+                );
+                html! { <div class="selection" style={ bounds.as_style() } /> }
+            }
+            _ => html! {},
+        }
+    }
+
+    fn is_won(&self) -> bool {
+        let piles = self.piles.iter().map(BoardPile::cards).collect::<Vec<_>>();
+        self.variant.is_won(&piles)
+    }
+
+    fn foundations_empty(&self) -> bool {
+        self.foundations().all(|(_, f)| f.is_empty())
+    }
+
+    fn start_cascade(&mut self) {
+        let window = web_sys::window().expect("no global `window` exists");
+        let dimension =
+            |value: Result<wasm_bindgen::JsValue, _>| value.ok().and_then(|v| v.as_f64());
+        let width = dimension(window.inner_width()).unwrap_or(1000.0);
+        let height = dimension(window.inner_height()).unwrap_or(800.0);
+
+        self.cascade = Some(Cascade::new(width, height));
+        self.request_cascade_frame();
+    }
+
+    fn request_cascade_frame(&mut self) {
+        self.cascade_task = Some(RenderService::request_animation_frame(
+            self.link.callback(Msg::CascadeFrame),
+        ));
+    }
+
+    // Launches the next card off the foundations, taking from each foundation in turn
+    fn launch_cascade_card(&mut self, timestamp: f64) {
+        let foundations = self.foundations().map(|(i, _)| i).collect::<Vec<_>>();
+        for offset in 0..foundations.len() {
+            let nth = (self.next_cascade_foundation + offset) % foundations.len();
+            let index = foundations[nth];
+            if let Some(card) = self.borrow_source(index).take_cards(1).pop() {
+                if let Some(cascade) = &mut self.cascade {
+                    cascade.launch(index, card, timestamp);
+                }
+                self.next_cascade_foundation = nth + 1;
+                return;
+            }
+        }
+    }
+
+    fn dismiss_cascade(&mut self) {
+        self.cascade_task = None;
+
+        if let Some(cascade) = self.cascade.take() {
+            // Cards were launched from the top of each foundation, so they go back in reverse
+            for (index, card) in cascade.into_cards().into_iter().rev() {
+                if let BoardPile::Foundation(foundation) = &mut self.piles[index] {
+                    foundation.restore_card(card);
+                }
+            }
+        }
+    }
+
+    // Where a pile sits on the page, in pixels
+    fn pile_position(spec: PileSpec) -> (i32, i32) {
+        (
+            PADDING + (spec.column * CARD_X_STRIDE as f32) as i32,
+            PADDING + (spec.row * CARD_Y_STRIDE as f32) as i32,
+        )
+    }
+
+    // A game of the chosen variant, dealt from a random seed
+    fn random_game(link: ComponentLink<Self>, options: Options) -> Self {
+        let seed = options.variant.rules(options.draw).random_seed();
+        Self::new_game(link, options, options.variant, seed, options.draw)
+    }
+
+    fn new_game(
+        link: ComponentLink<Self>,
+        options: Options,
+        variant_kind: VariantKind,
+        seed: u64,
+        draw: Draw,
+    ) -> Self {
+        let variant: Rc<dyn Variant> = Rc::from(variant_kind.rules(draw));
+        let specs = variant.piles();
+        let deal = variant.deal(seed);
+        let base = variant.foundation_base(seed);
+
+        // Every card starts out on the stock, and dealt cards fly out from there
+        let (stock_x, stock_y) = specs
+            .iter()
+            .find(|spec| spec.kind == PileKind::Stock)
+            .map_or((PADDING, PADDING), |spec| Self::pile_position(*spec));
+        let physical_card = |card: &Card| PhysicalCard::new(stock_x, stock_y, card);
+
+        let deal_queue = deal
+            .cards
+            .iter()
+            .map(|(pile, card, face_up)| (*pile, physical_card(card), *face_up))
+            .collect();
+        let mut stock_cards = Some(deal.stock.iter().map(physical_card).collect());
+        let piles = specs
+            .into_iter()
+            .map(|spec| {
+                let (x, y) = Self::pile_position(spec);
+                match spec.kind {
+                    PileKind::Stock => BoardPile::Stock(StockDiscard::from_cards(
+                        x,
+                        y,
+                        stock_cards.take().expect("there should only be one stock"),
+                        variant.clone(),
+                    )),
+                    PileKind::Foundation => {
+                        BoardPile::Foundation(Foundation::new(x, y, variant.clone(), base))
+                    }
+                    PileKind::Tableau => BoardPile::Tableau(Tableau::new(x, y, variant.clone())),
+                    PileKind::Cell => {
+                        BoardPile::Cell(CardSlot::new(x, y, spec.kind, variant.clone()))
+                    }
+                    PileKind::Pyramid => {
+                        BoardPile::Pyramid(CardSlot::new(x, y, spec.kind, variant.clone()))
+                    }
+                    PileKind::Reserve => BoardPile::Reserve(Reserve::new(x, y)),
+                }
+            })
+            .collect();
+
+        let deal_task = IntervalService::spawn(
+            Duration::from_millis(DEAL_INTERVAL_MS),
+            link.callback(|_| Msg::DealTick),
+        );
+        let verdict_search = OpenSolver::new(variant_kind.rules(draw), seed);
+        let verdict_task = verdict_search.as_ref().map(|_| {
+            IntervalService::spawn(
+                Duration::from_millis(SEARCH_INTERVAL_MS),
+                link.callback(|_| Msg::VerdictTick),
+            )
+        });
+
+        Self {
+            link,
+            variant_kind,
+            variant,
+            piles,
+            held_card: None,
+            press: None,
+            selection: None,
+            options,
+            seed,
+            draw,
+            rating: None,
+            moves: Vec::new(),
+            show_export: false,
+            show_import: false,
+            import_text: String::new(),
+            import_error: None,
+            replay: None,
+            show_share: false,
+            show_stats: false,
+            counted_played: false,
+            counted_won: false,
+            notice: None,
+            daily: None,
+            calendar: None,
+            started_at: js_sys::Date::now(),
+            seed_search: None,
+            search_daily: None,
+            search_task: None,
+            verdict_search,
+            verdict: None,
+            verdict_task,
+            deal_queue,
+            deal_task: Some(deal_task),
+            cascade: None,
+            cascade_task: None,
+            next_cascade_foundation: 0,
+        }
+    }
+
+    fn on_controls(e: &PointerEvent) -> bool {
+        e.target()
+            .and_then(|target| target.dyn_into::<Element>().ok())
+            .and_then(|element| element.closest(".controls").ok().flatten())
+            .is_some()
+    }
+
+    fn record(&self) -> GameRecord {
+        GameRecord {
+            variant: self.variant_kind,
+            seed: self.seed,
+            draw: self.draw,
+            moves: self.moves.clone(),
+        }
+    }
+
+    fn export_html(&self) -> Html {
+        let record = self.record().to_string();
+        let download_url = format!(
+            "data:text/plain;charset=utf-8,{}",
+            util::percent_encode(&record)
+        );
+        let filename = format!("solitaire-{}.txt", self.seed);
+
+        html! {
+            <div class="controls panel">
+                <textarea readonly=true rows=8 cols=40 value=record />
+                <br/>
+                <a href=download_url download=filename>{ "Download" }</a>
+                <button onclick=self.link.callback(|_| Msg::ToggleExport)>{ "Close" }</button>
+            </div>
+        }
+    }
+
+    // The page address without any query, which share links are built on
+    fn page_url() -> String {
+        let location = web_sys::window()
+            .expect("no global `window` exists")
+            .location();
+        let origin = location.origin().unwrap_or_default();
+        let pathname = location.pathname().unwrap_or_default();
+        format!("{}{}", origin, pathname)
+    }
+
+    // Reads the game from the `game` query parameter, if the page was opened from a share link
+    fn shared_record() -> Option<Result<GameRecord, String>> {
+        let search = web_sys::window()
+            .expect("no global `window` exists")
+            .location()
+            .search()
+            .unwrap_or_default();
+
+        search
+            .trim_start_matches('?')
+            .split('&')
+            .find_map(|parameter| parameter.strip_prefix("game="))
+            .map(|code| share::decode(code).map_err(|e| e.to_string()))
+    }
+
+    fn from_shared_record(
+        link: ComponentLink<Self>,
+        options: Options,
+        record: GameRecord,
+    ) -> Result<Self, String> {
+        if record.moves.is_empty() {
+            Ok(Self::new_game(
+                link,
+                options,
+                record.variant,
+                record.seed,
+                record.draw,
+            ))
+        } else {
+            Self::from_record(link, options, &record, record.moves.len()).map_err(|e| e.to_string())
+        }
+    }
+
+    fn share_html(&self) -> Html {
+        let record = self.record();
+        let deal_record = GameRecord {
+            moves: Vec::new(),
+            ..record.clone()
+        };
+        let deal_url = format!("{}?game={}", Self::page_url(), share::encode(&deal_record));
+        let position_url = format!("{}?game={}", Self::page_url(), share::encode(&record));
+
+        html! {
+            <div class="controls panel share">
+                <label>
+                    { "This deal" }
+                    <input readonly=true value=deal_url />
+                </label>
+                <label>
+                    { "This position" }
+                    <input readonly=true value=position_url />
+                </label>
+                <button onclick=self.link.callback(|_| Msg::ToggleShare)>{ "Close" }</button>
+            </div>
+        }
+    }
+
+    fn notice_html(&self) -> Html {
+        self.notice.as_ref().map_or(html! {}, |notice| {
+            html! {
+                <div class="controls panel">
+                    <div class="error">{ notice }</div>
+                    <button onclick=self.link.callback(|_| Msg::DismissNotice)>{ "OK" }</button>
+                </div>
+            }
+        })
+    }
+
+    fn play_daily(&mut self, date: Date) {
+        let deadline = js_sys::Date::now() + DAILY_SEARCH_TIME_BUDGET_MS;
+        self.start_seed_search(
+            SeedSearch::sequence(date.seed(), daily::DAILY_DRAW, deadline),
+            Some(date),
+        );
+    }
+
+    fn deal_new_game(&mut self) {
+        // The solver only knows Klondike
+        let wants_search = self.options.winnable_only || self.options.difficulty.is_some();
+        if wants_search && self.options.variant == VariantKind::Klondike {
+            let deadline = js_sys::Date::now() + SEARCH_TIME_BUDGET_MS;
+            let search = SeedSearch::random(self.options.draw, self.options.difficulty, deadline);
+            self.start_seed_search(search, None);
+        } else {
+            *self = Self::random_game(self.link.clone(), self.options);
+        }
+    }
+
+    // The current game carries on until the search finds a deal
+    fn start_seed_search(&mut self, search: SeedSearch, daily: Option<Date>) {
+        self.seed_search = Some(search);
+        self.search_daily = daily;
+        self.search_task = Some(IntervalService::spawn(
+            Duration::from_millis(SEARCH_INTERVAL_MS),
+            self.link.callback(|_| Msg::SearchTick),
+        ));
+    }
+
+    fn continue_seed_search(&mut self) -> ShouldRender {
+        let found = self.seed_search.as_mut().and_then(|search| {
+            search
+                .step(SEARCH_STEP_NODES, js_sys::Date::now())
+                .map(|(seed, rating)| (seed, rating, search.draw()))
+        });
+
+        match found {
+            Some((seed, rating, draw)) => {
+                let daily = self.search_daily;
+                *self = Self::new_game(
+                    self.link.clone(),
+                    self.options,
+                    VariantKind::Klondike,
+                    seed,
+                    draw,
+                );
+                self.daily = daily;
+                self.rating = Some(rating);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn continue_verdict_search(&mut self) -> ShouldRender {
+        let verdict = self
+            .verdict_search
+            .as_mut()
+            .and_then(|search| search.step(SEARCH_STEP_NODES));
+
+        if verdict.is_some() {
+            self.verdict = verdict;
+            self.verdict_search = None;
+            self.verdict_task = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn seconds_played(&self) -> u32 {
+        ((js_sys::Date::now() - self.started_at) / 1000.0) as u32
+    }
+
+    // Counts the game towards the statistics for its variant once the first move is made, and
+    // again once it's won. Records being viewed don't count until they're played on from.
+    fn update_stats(&mut self) {
+        if self.moves.is_empty() || self.replay.is_some() {
+            return;
+        }
+
+        if !self.counted_played {
+            VariantStats::record_played(self.variant_kind);
+            self.counted_played = true;
+        }
+        if !self.counted_won && self.is_won() {
+            VariantStats::record_won(self.variant_kind, self.seconds_played());
+            self.counted_won = true;
+        }
+    }
+
+    // Keeps the stored result for today's daily deal up to date after each move
+    fn save_daily_result(&self) {
+        if let (Some(date), false) = (self.daily, self.moves.is_empty()) {
+            let result = DailyResult {
+                won: self.is_won(),
+                seconds: self.seconds_played(),
+                moves: self.moves.len(),
+            };
+            result.save(date);
+        }
     }
 
     fn setup_event_callbacks(document: &Document, link: &ComponentLink<Self>) {
@@ -239,8 +1252,14 @@ impl Model {
         document.set_onpointerup(Some(pointerup_closure.as_ref().unchecked_ref()));
         pointerup_closure.forget();
 
-        let pointerdown_callback =
-            link.callback(|e: PointerEvent| Msg::MouseDown(e.page_x(), e.page_y()));
+        // Presses on buttons and panels shouldn't also act on the cards underneath them
+        let pointerdown_callback = link.batch_callback(|e: PointerEvent| {
+            if Self::on_controls(&e) {
+                None
+            } else {
+                Some(Msg::MouseDown(e.page_x(), e.page_y()))
+            }
+        });
         let pointerdown_closure =
             Closure::wrap(
                 Box::new(move |e: PointerEvent| pointerdown_callback.emit(e))
@@ -258,50 +1277,6 @@ impl Model {
             );
         document.set_onpointermove(Some(pointermove_closure.as_ref().unchecked_ref()));
         pointermove_closure.forget();
-
-        /*
-        let touchstart_callback = link.callback(|e: TouchEvent| {
-            let touch = e.touches().get(0).unwrap();
-            Msg::MouseDown(touch.page_x(), touch.page_y())
-        });
-        let touchstart_closure =
-            Closure::wrap(Box::new(move |e: TouchEvent| touchstart_callback.emit(e))
-                as Box<dyn FnMut(TouchEvent)>);
-        document.set_ontouchstart(Some(touchstart_closure.as_ref().unchecked_ref()));
-        touchstart_closure.forget();
-        */
-
-        let touchend_callback = link.callback(|e: TouchEvent| {
-            let touch = e.touches().get(0).unwrap();
-            Msg::Touch(touch.page_x(), touch.page_y())
-        });
-        let touchend_closure =
-            Closure::wrap(Box::new(move |e: TouchEvent| touchend_callback.emit(e))
-                as Box<dyn FnMut(TouchEvent)>);
-        document.set_ontouchend(Some(touchend_closure.as_ref().unchecked_ref()));
-        touchend_closure.forget();
-
-        let touchcancel_callback = link.callback(|e: TouchEvent| {
-            let touch = e.touches().get(0).unwrap();
-            Msg::Touch(touch.page_x(), touch.page_y())
-        });
-        let touchcancel_closure =
-            Closure::wrap(Box::new(move |e: TouchEvent| touchcancel_callback.emit(e))
-                as Box<dyn FnMut(TouchEvent)>);
-        document.set_ontouchcancel(Some(touchcancel_closure.as_ref().unchecked_ref()));
-        touchcancel_closure.forget();
-
-        /*
-        let touchmove_callback = link.callback(|e: TouchEvent| {
-            let touch = e.touches().get(0).unwrap();
-            Msg::MouseMove(touch.page_x(), touch.page_y())
-        });
-        let touchmove_closure =
-            Closure::wrap(Box::new(move |e: TouchEvent| touchmove_callback.emit(e))
-                as Box<dyn FnMut(TouchEvent)>);
-        document.set_ontouchmove(Some(touchmove_closure.as_ref().unchecked_ref()));
-        touchmove_closure.forget();
-        */
     }
 }
 
@@ -310,135 +1285,43 @@ impl Component for Model {
     type Properties = ();
 
     fn create(_props: Self::Properties, link: ComponentLink<Self>) -> Self {
-        let mut stock_cards: Vec<PhysicalCard> =
-            DECK.iter().map(|c| PhysicalCard::new(0, 0, c)).collect();
-        stock_cards.shuffle(&mut thread_rng());
-
-        let tableau1_cards: Vec<PhysicalCard> = stock_cards.drain(0..1).collect();
-        let tableau2_cards: Vec<PhysicalCard> = stock_cards.drain(0..2).collect();
-        let tableau3_cards: Vec<PhysicalCard> = stock_cards.drain(0..3).collect();
-        let tableau4_cards: Vec<PhysicalCard> = stock_cards.drain(0..4).collect();
-        let tableau5_cards: Vec<PhysicalCard> = stock_cards.drain(0..5).collect();
-        let tableau6_cards: Vec<PhysicalCard> = stock_cards.drain(0..6).collect();
-        let tableau7_cards: Vec<PhysicalCard> = stock_cards.drain(0..7).collect();
-
         let window = web_sys::window().expect("no global `window` exists");
         let document = window.document().expect("should have a document on window");
 
         Self::setup_event_callbacks(&document, &link);
 
-        Self {
-            link,
-            stock_discard: StockDiscard::from_cards(STOCK_DISCARD_X, STOCK_DISCARD_Y, stock_cards),
-            foundation1: Foundation::new(
-                FOUNDATIONS_X,
-                FOUNDATIONS_Y,
-                CardSinks::Foundation1,
-                CardSources::Foundation1,
-            ),
-            foundation2: Foundation::new(
-                FOUNDATIONS_X + CARD_X_STRIDE,
-                FOUNDATIONS_Y,
-                CardSinks::Foundation2,
-                CardSources::Foundation2,
-            ),
-            foundation3: Foundation::new(
-                FOUNDATIONS_X + CARD_X_STRIDE * 2,
-                FOUNDATIONS_Y,
-                CardSinks::Foundation3,
-                CardSources::Foundation3,
-            ),
-            foundation4: Foundation::new(
-                FOUNDATIONS_X + CARD_X_STRIDE * 3,
-                FOUNDATIONS_Y,
-                CardSinks::Foundation4,
-                CardSources::Foundation4,
-            ),
-            tableau1: Tableau::from_cards(
-                TABLEAUS_X,
-                TABLEAUS_Y,
-                tableau1_cards,
-                CardSinks::Tableau1,
-                CardSources::Tableau1,
-            ),
-            tableau2: Tableau::from_cards(
-                TABLEAUS_X + CARD_X_STRIDE,
-                TABLEAUS_Y,
-                tableau2_cards,
-                CardSinks::Tableau2,
-                CardSources::Tableau2,
-            ),
-            tableau3: Tableau::from_cards(
-                TABLEAUS_X + CARD_X_STRIDE * 2,
-                TABLEAUS_Y,
-                tableau3_cards,
-                CardSinks::Tableau3,
-                CardSources::Tableau3,
-            ),
-            tableau4: Tableau::from_cards(
-                TABLEAUS_X + CARD_X_STRIDE * 3,
-                TABLEAUS_Y,
-                tableau4_cards,
-                CardSinks::Tableau4,
-                CardSources::Tableau4,
-            ),
-            tableau5: Tableau::from_cards(
-                TABLEAUS_X + CARD_X_STRIDE * 4,
-                TABLEAUS_Y,
-                tableau5_cards,
-                CardSinks::Tableau5,
-                CardSources::Tableau5,
-            ),
-            tableau6: Tableau::from_cards(
-                TABLEAUS_X + CARD_X_STRIDE * 5,
-                TABLEAUS_Y,
-                tableau6_cards,
-                CardSinks::Tableau6,
-                CardSources::Tableau6,
-            ),
-            tableau7: Tableau::from_cards(
-                TABLEAUS_X + CARD_X_STRIDE * 6,
-                TABLEAUS_Y,
-                tableau7_cards,
-                CardSinks::Tableau7,
-                CardSources::Tableau7,
-            ),
-            held_card: None,
+        let options = Options::default();
+        match Self::shared_record()
+            .map(|record| record.and_then(|r| Self::from_shared_record(link.clone(), options, r)))
+        {
+            Some(Ok(model)) => model,
+            Some(Err(error)) => {
+                let mut model = Self::random_game(link, options);
+                model.notice = Some(format!(
+                    "The link could not be opened, so a new game was dealt instead: {}",
+                    error
+                ));
+                model
+            }
+            None => Self::random_game(link, options),
         }
     }
 
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::MouseUp(mouse_x, mouse_y) => {
-                let mut result = false;
-
-                let mut held_card = None;
-                std::mem::swap(&mut self.held_card, &mut held_card);
-
-                if let Some(held_card) = held_card {
-                    let cards = self
-                        .borrow_source(held_card.source())
-                        .peek_cards(held_card.count());
-
-                    if let Some(sink) = self
-                        .borrow_sinks()
-                        .iter_mut()
-                        .find(|s| {
-                            s.within_bounds(mouse_x, mouse_y) && s.is_placement_possible(&cards)
-                        })
-                        .map(|s| s.card_sink())
-                    {
-                        // Place card
-                        let source = self.borrow_source(held_card.source());
-                        let physical_cards = source.take_cards(held_card.count());
-
-                        self.borrow_sink(sink)
-                            .place_cards(mouse_x, mouse_y, physical_cards)
-                            .expect("placement should be possible");
-
-                        self.held_card = None;
-                        result = true;
-                    } else {
+                if let Some(mut held_card) = self.held_card.take() {
+                    held_card.set_mouse_position(mouse_x, mouse_y);
+                    let selection = Selection {
+                        source: held_card.source(),
+                        count: held_card.count(),
+                    };
+
+                    let placed = self
+                        .drop_target(&held_card)
+                        .is_some_and(|sink| self.move_cards(selection, sink, mouse_x, mouse_y));
+
+                    if !placed {
                         // Return card
                         let physical_cards = self
                             .borrow_source(held_card.source())
@@ -451,69 +1334,184 @@ impl Component for Model {
                                 mouse_y - CARD_HEIGHT as i32 / 2,
                             );
                         }
-
-                        self.held_card = None;
-                        result = true;
                     }
-                }
 
-                result
+                    self.save_daily_result();
+                    self.update_stats();
+                    self.start_cascade_if_won();
+                    true
+                } else if let Some(press) = self.press.take() {
+                    let result = self.click(press);
+                    self.save_daily_result();
+                    self.update_stats();
+                    self.start_cascade_if_won();
+                    result
+                } else {
+                    false
+                }
+            }
+            // Cards can't be moved by hand while a record is being viewed
+            Msg::MouseDown(_, _) if self.replay.is_some() => false,
+            Msg::MouseDown(_, _) if self.cascade.is_some() => {
+                self.dismiss_cascade();
+                true
+            }
+            Msg::MouseDown(_, _) if self.is_dealing() => {
+                // Clicking anywhere during the opening deal skips it
+                self.finish_deal();
+                true
             }
             Msg::MouseDown(mouse_x, mouse_y) => {
-                self.stock_discard.handle_click(mouse_x, mouse_y) || {
-                    let mut result = false;
-                    if self.held_card.is_none() {
-                        if let Some((source, count)) =
-                            self.borrow_sources().iter_mut().find_map(|source| {
-                                let count = source.how_many_cards(mouse_x, mouse_y);
-                                if count == 0 {
-                                    None
-                                } else {
-                                    Some((source, count))
-                                }
-                            })
-                        {
-                            source
-                                .borrow_cards_mut(count)
-                                .iter_mut()
-                                .for_each(|c| c.set_visible(false));
-                            result = true;
-                            let cards = source.peek_cards(count);
-
-                            let (x, y) = source.borrow_cards(count).first().unwrap().position();
-                            self.held_card = Some(HeldCard::new(
-                                cards,
-                                source.card_source(),
-                                count,
-                                x,
-                                y,
-                                mouse_x,
-                                mouse_y,
-                            ));
-                        } else {
-                            self.held_card = None;
-                        }
-                    }
-                    result
+                let on_stock = self
+                    .stock_discard_mut()
+                    .is_some_and(|s| s.stock_contains(mouse_x, mouse_y));
+                let stock_move = if on_stock {
+                    self.deal_from_stock()
+                } else {
+                    None
+                };
+                if let Some(stock_move) = stock_move {
+                    self.moves.push(stock_move);
+                    self.selection = None;
+                    self.save_daily_result();
+                    self.update_stats();
+                    true
+                } else {
+                    self.press = Some(Press {
+                        x: mouse_x,
+                        y: mouse_y,
+                        cards: self.cards_at(mouse_x, mouse_y),
+                    });
+                    false
                 }
             }
             Msg::MouseMove(x, y) => {
                 if let Some(held_card) = &mut self.held_card {
                     held_card.set_mouse_position(x, y);
                     true
+                } else if let Some(press) = self.press.filter(|p| p.is_drag(x, y)) {
+                    self.press = None;
+
+                    if let Some(cards) = press.cards {
+                        self.selection = None;
+                        self.start_drag(cards, x, y);
+                        true
+                    } else {
+                        false
+                    }
                 } else {
                     false
                 }
             }
-            Msg::Touch(x, y) => {
-                if self.held_card.is_some() {
-                    self.link.send_message(Msg::MouseUp(x, y))
-                } else {
-                    self.link.send_message(Msg::MouseDown(x, y));
-                    self.link.send_message(Msg::MouseMove(300, 600));
+            Msg::DealTick => {
+                self.deal_next_card(true);
+                true
+            }
+            Msg::CascadeFrame(timestamp) => {
+                if self
+                    .cascade
+                    .as_ref()
+                    .is_some_and(|c| c.should_launch(timestamp))
+                {
+                    self.launch_cascade_card(timestamp);
                 }
+
+                let cards_remaining = !self.foundations_empty();
+                if let Some(cascade) = &mut self.cascade {
+                    cascade.advance(timestamp);
+
+                    if cascade.is_flying() || cards_remaining {
+                        self.request_cascade_frame();
+                    } else {
+                        self.cascade_task = None;
+                    }
+                }
+                true
+            }
+            Msg::SearchTick => self.continue_seed_search(),
+            Msg::VerdictTick => self.continue_verdict_search(),
+            Msg::NewGame => {
+                self.deal_new_game();
+                true
+            }
+            Msg::ToggleOneClickMoves => {
+                self.options.one_click_moves = !self.options.one_click_moves;
+                true
+            }
+            Msg::ToggleDrawOne => {
+                self.options.draw = match self.options.draw {
+                    Draw::One => Draw::Three,
+                    Draw::Three => Draw::One,
+                };
+                true
+            }
+            Msg::ToggleWinnableOnly => {
+                self.options.winnable_only = !self.options.winnable_only;
+                true
+            }
+            Msg::SetDifficulty(difficulty) => {
+                self.options.difficulty = difficulty;
+                true
+            }
+            Msg::SetVariant(variant) => {
+                self.options.variant = variant;
+                true
+            }
+            Msg::ToggleExport => {
+                self.show_export = !self.show_export;
+                true
+            }
+            Msg::ToggleShare => {
+                self.show_share = !self.show_share;
+                true
+            }
+            Msg::ToggleStats => {
+                self.show_stats = !self.show_stats;
+                true
+            }
+            Msg::DismissNotice => {
+                self.notice = None;
+                true
+            }
+            Msg::ToggleImport => {
+                self.show_import = !self.show_import;
+                self.import_error = None;
+                true
+            }
+            Msg::ImportInput(text) => {
+                self.import_text = text;
                 false
             }
+            Msg::LoadRecord => {
+                if let Err(error) = self.load_record() {
+                    self.import_error = Some(error.to_string());
+                }
+                true
+            }
+            Msg::Replay(replay_msg) => self.update_replay(replay_msg),
+            Msg::ToggleCalendar => {
+                self.calendar = match self.calendar {
+                    Some(_) => None,
+                    None => Some(Calendar::this_month()),
+                };
+                true
+            }
+            Msg::Calendar(CalendarMsg::PreviousMonth) => {
+                if let Some(calendar) = &mut self.calendar {
+                    calendar.previous_month();
+                }
+                true
+            }
+            Msg::Calendar(CalendarMsg::NextMonth) => {
+                if let Some(calendar) = &mut self.calendar {
+                    calendar.next_month();
+                }
+                true
+            }
+            Msg::Calendar(CalendarMsg::Play(date)) => {
+                self.play_daily(date);
+                true
+            }
         }
     }
 
@@ -526,23 +1524,149 @@ impl Component for Model {
 
     fn view(&self) -> Html {
         let held_card_html = self.held_card.as_ref().map_or(html! {}, |c| c.as_html());
+        let drop_highlights_html = self
+            .held_card
+            .as_ref()
+            .map_or(html! {}, |c| self.drop_highlights_html(c));
+        let cascade_html = self.cascade.as_ref().map_or(html! {}, |c| c.as_html());
+        let export_html = if self.show_export {
+            self.export_html()
+        } else {
+            html! {}
+        };
+        let import_html = if self.show_import {
+            self.import_html()
+        } else {
+            html! {}
+        };
+        let share_html = if self.show_share {
+            self.share_html()
+        } else {
+            html! {}
+        };
+        let stats_html = if self.show_stats {
+            stats::stats_html(&self.link)
+        } else {
+            html! {}
+        };
+        let calendar_html = self
+            .calendar
+            .as_ref()
+            .map_or(html! {}, |c| c.as_html(&self.link));
+        let deal_name = match (self.daily, self.variant_kind) {
+            (Some(date), _) => format!("Daily deal {}", date),
+            (None, VariantKind::Klondike) => format!("Deal {}", self.seed),
+            (None, variant_kind) => format!("{} deal {}", variant_kind, self.seed),
+        };
+        let deal_html = match self.rating {
+            Some(rating) => html! { <label>{ format!("{} ({})", deal_name, rating) }</label> },
+            None => html! { <label>{ deal_name }</label> },
+        };
+        let score_html = if self.variant.scores_streaks() {
+            let score = StreakScore::from_moves(&self.moves);
+            html! { <label>{ format!("Score {} (streak {})", score.points, score.streak) }</label> }
+        } else {
+            html! {}
+        };
+        let verdict_html = match (&self.verdict_search, &self.verdict) {
+            (Some(_), _) => html! { <label>{ "Checking whether this deal can be won..." }</label> },
+            (None, Some(SolveResult::Solved(_))) => html! { <label>{ "Can be won" }</label> },
+            (None, Some(SolveResult::Unsolvable)) => html! { <label>{ "Can't be won" }</label> },
+            (None, Some(SolveResult::GaveUp)) => {
+                html! { <label>{ "Couldn't tell whether this deal can be won" }</label> }
+            }
+            (None, None) => html! {},
+        };
+        let search_html = if self.seed_search.is_some() {
+            html! { <label>{ "Finding a winnable deal..." }</label> }
+        } else {
+            html! {}
+        };
+        let replay_html = self
+            .replay
+            .as_ref()
+            .map_or(html! {}, |r| r.as_html(&self.link));
         html! {
             <div>
                 /* <button onclick=self.link.callback(|_| Msg::StockToDiscard)>{ "Deal" }</button> */
                 <br/>
-                { self.foundation1.as_html() }
-                { self.foundation2.as_html() }
-                { self.foundation3.as_html() }
-                { self.foundation4.as_html() }
-                { self.tableau1.as_html() }
-                { self.tableau2.as_html() }
-                { self.tableau3.as_html() }
-                { self.tableau4.as_html() }
-                { self.tableau5.as_html() }
-                { self.tableau6.as_html() }
-                { self.tableau7.as_html() }
-                { self.stock_discard.as_html() }
+                { for self.piles.iter().map(BoardPile::as_html) }
+                { self.selection_html() }
+                { drop_highlights_html }
                 { held_card_html }
+                { cascade_html }
+                <div class="controls toolbar">
+                    { search_html }
+                    { deal_html }
+                    { score_html }
+                    { verdict_html }
+                    <label title="Takes effect from the next game">
+                        { "Game " }
+                        <select onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(select) => {
+                                Msg::SetVariant(select.value().parse().unwrap_or_default())
+                            }
+                            _ => Msg::SetVariant(VariantKind::default()),
+                        })>
+                            { for VariantKind::ALL.iter().map(|v| html! {
+                                <option value=v.to_string() selected=self.options.variant == *v>
+                                    { v }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                    <label>
+                        <input
+                            type="checkbox"
+                            checked=self.options.one_click_moves
+                            onchange=self.link.callback(|_| Msg::ToggleOneClickMoves)
+                        />
+                        { "One-click moves" }
+                    </label>
+                    <label title="Takes effect from the next game">
+                        <input
+                            type="checkbox"
+                            checked=self.options.draw == Draw::One
+                            onchange=self.link.callback(|_| Msg::ToggleDrawOne)
+                        />
+                        { "Draw one" }
+                    </label>
+                    <label title="Only deal games the solver has found a way to win (Klondike only)">
+                        <input
+                            type="checkbox"
+                            checked=self.options.winnable_only
+                            onchange=self.link.callback(|_| Msg::ToggleWinnableOnly)
+                        />
+                        { "Winnable deals" }
+                    </label>
+                    <label title="Deals are also winnable when a difficulty is chosen (Klondike only)">
+                        { "Difficulty " }
+                        <select onchange=self.link.callback(|e: ChangeData| match e {
+                            ChangeData::Select(select) => Msg::SetDifficulty(select.value().parse().ok()),
+                            _ => Msg::SetDifficulty(None),
+                        })>
+                            <option value="" selected=self.options.difficulty.is_none()>{ "Any" }</option>
+                            { for Difficulty::ALL.iter().map(|d| html! {
+                                <option value=d.to_string() selected=self.options.difficulty == Some(*d)>
+                                    { d }
+                                </option>
+                            })}
+                        </select>
+                    </label>
+                    <button onclick=self.link.callback(|_| Msg::ToggleCalendar)>{ "Daily deal" }</button>
+                    <button onclick=self.link.callback(|_| Msg::ToggleShare)>{ "Share" }</button>
+                    <button onclick=self.link.callback(|_| Msg::ToggleStats)>{ "Statistics" }</button>
+                    <button onclick=self.link.callback(|_| Msg::ToggleExport)>{ "Export game" }</button>
+                    <button onclick=self.link.callback(|_| Msg::ToggleImport)>{ "Import game" }</button>
+                    <button onclick=self.link.callback(|_| Msg::NewGame)>{ "New game" }</button>
+                    { self.notice_html() }
+                    { calendar_html }
+                    { share_html }
+                    { stats_html }
+                    { export_html }
+                    { import_html }
+                </div>
+                { replay_html }
             </div>
         }
     }
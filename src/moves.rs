@@ -0,0 +1,103 @@
+use std::{fmt, str::FromStr};
+
+/// A pile as it is named in move notation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pile {
+    Waste,
+    // Foundations, tableaus and cells are numbered from 0 here, but from 1 in the notation
+    Foundation(usize),
+    Tableau(usize),
+    // A free cell, in games that have them
+    Cell(usize),
+    // A place in Pyramid's pyramid, numbered along each row from the top
+    Pyramid(usize),
+    // Canfield's reserve, which there's only ever one of
+    Reserve,
+}
+
+impl fmt::Display for Pile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pile::Waste => write!(f, "W"),
+            Pile::Foundation(index) => write!(f, "F{}", index + 1),
+            Pile::Tableau(index) => write!(f, "T{}", index + 1),
+            Pile::Cell(index) => write!(f, "C{}", index + 1),
+            Pile::Pyramid(index) => write!(f, "P{}", index + 1),
+            Pile::Reserve => write!(f, "R"),
+        }
+    }
+}
+
+impl FromStr for Pile {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = |n: &str| match n.parse::<usize>() {
+            Ok(n) if n > 0 => Ok(n - 1),
+            _ => Err(()),
+        };
+
+        let mut chars = s.chars();
+        match (chars.next(), chars.as_str()) {
+            (Some('W'), "") => Ok(Pile::Waste),
+            (Some('R'), "") => Ok(Pile::Reserve),
+            (Some('F'), n) => number(n).map(Pile::Foundation),
+            (Some('T'), n) => number(n).map(Pile::Tableau),
+            (Some('C'), n) => number(n).map(Pile::Cell),
+            (Some('P'), n) => number(n).map(Pile::Pyramid),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A single move, written as `D` for a draw, `R` for recycling the waste back into the stock,
+/// or `W-T3` for a card moving from the waste to the third tableau. When more than one card
+/// moves, the count follows the pile it came from, as in `T5(3)-T1`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Move {
+    Draw,
+    Recycle,
+    Transfer { from: Pile, count: usize, to: Pile },
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Draw => write!(f, "D"),
+            Move::Recycle => write!(f, "R"),
+            Move::Transfer { from, count, to } if *count == 1 => write!(f, "{}-{}", from, to),
+            Move::Transfer { from, count, to } => write!(f, "{}({})-{}", from, count, to),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "D" => Ok(Move::Draw),
+            "R" => Ok(Move::Recycle),
+            _ => {
+                let (from, to) = s.split_once('-').ok_or(())?;
+                let (from, count) = match from.strip_suffix(')') {
+                    Some(from) => {
+                        let (from, count) = from.split_once('(').ok_or(())?;
+                        (from, count.parse::<usize>().map_err(|_| ())?)
+                    }
+                    None => (from, 1),
+                };
+
+                if count == 0 {
+                    return Err(());
+                }
+
+                Ok(Move::Transfer {
+                    from: from.parse()?,
+                    count,
+                    to: to.parse()?,
+                })
+            }
+        }
+    }
+}
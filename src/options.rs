@@ -0,0 +1,33 @@
+use crate::{difficulty::Difficulty, variant::VariantKind};
+
+/// How many cards are turned over from the stock at a time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Draw {
+    One,
+    #[default]
+    Three,
+}
+
+impl Draw {
+    pub fn count(&self) -> usize {
+        match self {
+            Draw::One => 1,
+            Draw::Three => 3,
+        }
+    }
+}
+
+/// Player preferences that carry over from one game to the next.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    // Which game new deals are for
+    pub variant: VariantKind,
+    // Clicking a face-up card moves it straight to the best place it can go
+    pub one_click_moves: bool,
+    // Only takes effect when a new game is dealt
+    pub draw: Draw,
+    // New games are only dealt from seeds the solver has found a win for
+    pub winnable_only: bool,
+    // Choosing a difficulty also means deals have to be winnable, so they can be rated
+    pub difficulty: Option<Difficulty>,
+}
@@ -0,0 +1,187 @@
+use solitaire_web::{
+    card::Card,
+    variant::{PileKind, Variant},
+};
+use yew::Html;
+
+use crate::{
+    card_slot::CardSlot,
+    foundation::Foundation,
+    physical_card::{CardSink, CardSource, PhysicalCard},
+    reserve::Reserve,
+    stock_discard::StockDiscard,
+    tableau::Tableau,
+};
+
+/// One of the piles on the board. The model keeps them in the order the variant lists them, and
+/// refers to them by that index.
+pub enum BoardPile {
+    Stock(StockDiscard),
+    Foundation(Foundation),
+    Tableau(Tableau),
+    Cell(CardSlot),
+    Pyramid(CardSlot),
+    Reserve(Reserve),
+}
+
+impl BoardPile {
+    pub fn kind(&self) -> PileKind {
+        match self {
+            Self::Stock(_) => PileKind::Stock,
+            Self::Foundation(_) => PileKind::Foundation,
+            Self::Tableau(_) => PileKind::Tableau,
+            Self::Cell(_) => PileKind::Cell,
+            Self::Pyramid(_) => PileKind::Pyramid,
+            Self::Reserve(_) => PileKind::Reserve,
+        }
+    }
+
+    // Cards are taken from and paired with the stock's waste, not the stock itself
+    pub fn source(&self) -> Option<&dyn CardSource> {
+        match self {
+            Self::Stock(stock_discard) => Some(stock_discard.discard()),
+            Self::Foundation(foundation) => Some(foundation),
+            Self::Tableau(tableau) => Some(tableau),
+            Self::Cell(slot) | Self::Pyramid(slot) => Some(slot),
+            Self::Reserve(reserve) => Some(reserve),
+        }
+    }
+
+    pub fn source_mut(&mut self) -> Option<&mut dyn CardSource> {
+        match self {
+            Self::Stock(stock_discard) => Some(stock_discard.discard_mut()),
+            Self::Foundation(foundation) => Some(foundation),
+            Self::Tableau(tableau) => Some(tableau),
+            Self::Cell(slot) | Self::Pyramid(slot) => Some(slot),
+            Self::Reserve(reserve) => Some(reserve),
+        }
+    }
+
+    pub fn sink(&self) -> Option<&dyn CardSink> {
+        match self {
+            Self::Stock(stock_discard) => stock_discard.waste().map(|w| w as &dyn CardSink),
+            Self::Foundation(foundation) => Some(foundation),
+            Self::Tableau(tableau) => Some(tableau),
+            Self::Cell(slot) | Self::Pyramid(slot) => Some(slot),
+            // Nothing can be put onto a reserve
+            Self::Reserve(_) => None,
+        }
+    }
+
+    pub fn sink_mut(&mut self) -> Option<&mut dyn CardSink> {
+        match self {
+            Self::Stock(stock_discard) => stock_discard.waste_mut().map(|w| w as &mut dyn CardSink),
+            Self::Foundation(foundation) => Some(foundation),
+            Self::Tableau(tableau) => Some(tableau),
+            Self::Cell(slot) | Self::Pyramid(slot) => Some(slot),
+            Self::Reserve(_) => None,
+        }
+    }
+
+    pub fn deal_card(&mut self, card: PhysicalCard, face_up: bool, animate: bool) {
+        match self {
+            Self::Tableau(tableau) => tableau.deal_card(card, face_up, animate),
+            Self::Pyramid(slot) => slot.deal_card(card, face_up, animate),
+            Self::Foundation(foundation) => foundation.deal_card(card, face_up, animate),
+            Self::Reserve(reserve) => reserve.deal_card(card, face_up, animate),
+            // Cards dealt to the stock are turned over onto its waste
+            Self::Stock(stock_discard) => stock_discard.deal_to_waste(card),
+            Self::Cell(_) => unreachable!("cards are never dealt into free cells"),
+        }
+    }
+
+    // Every card in the pile, bottom first, with the stock's followed by its waste's
+    pub fn cards(&self) -> Vec<Card> {
+        match self {
+            Self::Stock(stock_discard) => stock_discard.cards(),
+            Self::Foundation(foundation) => foundation.cards(),
+            Self::Tableau(tableau) => tableau.cards(),
+            Self::Cell(slot) | Self::Pyramid(slot) => slot.card().into_iter().collect(),
+            Self::Reserve(reserve) => reserve.cards(),
+        }
+    }
+
+    pub fn as_html(&self) -> Html {
+        match self {
+            Self::Stock(stock_discard) => stock_discard.as_html(),
+            Self::Foundation(foundation) => foundation.as_html(),
+            Self::Tableau(tableau) => tableau.as_html(),
+            Self::Cell(slot) | Self::Pyramid(slot) => slot.as_html(),
+            Self::Reserve(reserve) => reserve.as_html(),
+        }
+    }
+}
+
+/// Whether nothing is left on the piles that cover `piles[index]`, so it can be played to or
+/// from.
+pub fn is_uncovered(variant: &dyn Variant, piles: &[BoardPile], index: usize) -> bool {
+    variant
+        .covered_by(index)
+        .into_iter()
+        .all(|i| piles[i].cards().is_empty())
+}
+
+/// Whether the top `count` cards of `piles[index]` can be picked up: the pile isn't covered, and
+/// they're all there and face up.
+pub fn can_pick_up(variant: &dyn Variant, piles: &[BoardPile], index: usize, count: usize) -> bool {
+    let cards = piles[index]
+        .source()
+        .map_or_else(Vec::new, |source| source.borrow_cards(count));
+    is_uncovered(variant, piles, index)
+        && cards.len() == count
+        && cards.iter().all(|card| !card.flipped())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use solitaire_web::{card::Value, variant::Pyramid};
+
+    use super::*;
+
+    // The board for a Pyramid deal, with every card already in place
+    fn pyramid_piles(seed: u64) -> Vec<BoardPile> {
+        let variant: Rc<dyn Variant> = Rc::new(Pyramid);
+        let mut piles = variant
+            .piles()
+            .into_iter()
+            .map(|spec| match spec.kind {
+                PileKind::Foundation => {
+                    BoardPile::Foundation(Foundation::new(0, 0, variant.clone(), Value::Ace))
+                }
+                PileKind::Stock => {
+                    BoardPile::Stock(StockDiscard::from_cards(0, 0, Vec::new(), variant.clone()))
+                }
+                kind => BoardPile::Pyramid(CardSlot::new(0, 0, kind, variant.clone())),
+            })
+            .collect::<Vec<_>>();
+        for (pile, card, face_up) in variant.deal(seed).cards {
+            piles[pile].deal_card(PhysicalCard::new(0, 0, &card), face_up, false);
+        }
+        piles
+    }
+
+    #[test]
+    fn covered_cards_cant_be_picked_up() {
+        let mut piles = pyramid_piles(1);
+        // The first card of the sixth row, and the two cards in the last row on top of it
+        let (covered, left, right) = (16, 22, 23);
+        assert!(!can_pick_up(&Pyramid, &piles, covered, 1));
+        assert!(can_pick_up(&Pyramid, &piles, left, 1));
+
+        piles[left].source_mut().unwrap().take_cards(1);
+        assert!(!can_pick_up(&Pyramid, &piles, covered, 1));
+        piles[right].source_mut().unwrap().take_cards(1);
+        assert!(can_pick_up(&Pyramid, &piles, covered, 1));
+    }
+
+    #[test]
+    fn face_down_cards_cant_be_picked_up() {
+        let mut piles = pyramid_piles(1);
+        let card = PhysicalCard::new(0, 0, &piles[22].cards()[0]);
+        piles[22].source_mut().unwrap().take_cards(1);
+        piles[22].deal_card(card, false, false);
+        assert!(!can_pick_up(&Pyramid, &piles, 22, 1));
+    }
+}
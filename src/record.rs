@@ -0,0 +1,116 @@
+use std::{fmt, str::FromStr};
+
+use crate::{moves::Move, options::Draw, variant::VariantKind};
+
+/// Everything needed to play a game again from the start.
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    pub variant: VariantKind,
+    pub seed: u64,
+    pub draw: Draw,
+    pub moves: Vec<Move>,
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Game: {}", self.variant)?;
+        writeln!(f, "Seed: {}", self.seed)?;
+        writeln!(f, "Draw: {}", self.draw.count())?;
+        write!(f, "Moves:")?;
+        for game_move in self.moves.iter() {
+            write!(f, " {}", game_move)?;
+        }
+        writeln!(f)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordError {
+    MissingSeed,
+    InvalidGame(String),
+    // A game this program can't play, such as one only the web version has
+    UnsupportedGame(VariantKind),
+    InvalidSeed(String),
+    InvalidDraw(String),
+    // Moves are numbered from 1
+    InvalidMove(usize, String),
+    IllegalMove(usize, Move),
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecordError::MissingSeed => write!(f, "The record has no seed"),
+            RecordError::InvalidGame(game) => write!(f, "\"{}\" is not a known game", game),
+            RecordError::UnsupportedGame(game) => write!(f, "{} games can't be played here", game),
+            RecordError::InvalidSeed(seed) => write!(f, "\"{}\" is not a valid seed", seed),
+            RecordError::InvalidDraw(draw) => {
+                write!(f, "\"{}\" is not a valid draw, it should be 1 or 3", draw)
+            }
+            RecordError::InvalidMove(number, text) => {
+                write!(f, "Move {} (\"{}\") is not valid notation", number, text)
+            }
+            RecordError::IllegalMove(number, game_move) => {
+                write!(f, "Move {} ({}) cannot be played", number, game_move)
+            }
+        }
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = RecordError;
+
+    // Reads the format written by `Display`. Lines that aren't recognised are skipped.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Records from before other games were added are all Klondike
+        let mut variant = VariantKind::default();
+        let mut seed = None;
+        let mut draw = Draw::default();
+        let mut moves = Vec::new();
+
+        for line in s.lines() {
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => continue,
+            };
+
+            match key {
+                "Game" => {
+                    variant = value
+                        .parse()
+                        .map_err(|_| RecordError::InvalidGame(value.to_string()))?;
+                }
+                "Seed" => {
+                    seed = Some(
+                        value
+                            .parse::<u64>()
+                            .map_err(|_| RecordError::InvalidSeed(value.to_string()))?,
+                    );
+                }
+                "Draw" => {
+                    draw = match value {
+                        "1" => Draw::One,
+                        "3" => Draw::Three,
+                        _ => return Err(RecordError::InvalidDraw(value.to_string())),
+                    };
+                }
+                "Moves" => {
+                    for text in value.split_whitespace() {
+                        let game_move = text.parse::<Move>().map_err(|_| {
+                            RecordError::InvalidMove(moves.len() + 1, text.to_string())
+                        })?;
+                        moves.push(game_move);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            variant,
+            seed: seed.ok_or(RecordError::MissingSeed)?,
+            draw,
+            moves,
+        })
+    }
+}
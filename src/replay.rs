@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+use solitaire_web::record::GameRecord;
+use yew::{
+    html,
+    services::{interval::IntervalTask, IntervalService},
+    ChangeData, ComponentLink, Html, InputData,
+};
+
+use crate::{Model, Msg};
+
+// Moves played per second
+const DEFAULT_SPEED: u32 = 2;
+const MAX_SPEED: u32 = 10;
+
+#[derive(Clone, Copy, Debug)]
+pub enum ReplayMsg {
+    Start,
+    Back,
+    TogglePlay,
+    Forward,
+    End,
+    Tick,
+    SetSpeed(u32),
+    Jump(usize),
+    Exit,
+}
+
+/// Steps through a loaded game record. The position is the number of moves played since the deal.
+pub struct Replay {
+    record: GameRecord,
+    position: usize,
+    speed: u32,
+    play_task: Option<IntervalTask>,
+}
+
+impl Replay {
+    pub fn new(record: GameRecord) -> Self {
+        Self {
+            record,
+            position: 0,
+            speed: DEFAULT_SPEED,
+            play_task: None,
+        }
+    }
+
+    pub fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    pub fn len(&self) -> usize {
+        self.record.moves.len()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.play_task.is_some()
+    }
+
+    pub fn play(&mut self, link: &ComponentLink<Model>) {
+        self.play_task = Some(IntervalService::spawn(
+            Duration::from_millis(1000 / self.speed as u64),
+            link.callback(|_| Msg::Replay(ReplayMsg::Tick)),
+        ));
+    }
+
+    pub fn pause(&mut self) {
+        self.play_task = None;
+    }
+
+    pub fn set_speed(&mut self, speed: u32, link: &ComponentLink<Model>) {
+        self.speed = speed.clamp(1, MAX_SPEED);
+        if self.is_playing() {
+            self.play(link);
+        }
+    }
+
+    pub fn as_html(&self, link: &ComponentLink<Model>) -> Html {
+        let last_move = self
+            .position
+            .checked_sub(1)
+            .map_or(String::from("Deal"), |i| self.record.moves[i].to_string());
+        let play_label = if self.is_playing() { "Pause" } else { "Play" };
+
+        html! {
+            <div class="controls panel viewer">
+                <div>
+                    { format!("Move {} / {}: {}", self.position, self.len(), last_move) }
+                </div>
+                <input
+                    type="range"
+                    min=0
+                    max=self.len().to_string()
+                    value=self.position.to_string()
+                    oninput=link.callback(|e: InputData| {
+                        Msg::Replay(ReplayMsg::Jump(e.value.parse().unwrap_or(0)))
+                    })
+                />
+                <div>
+                    <button onclick=link.callback(|_| Msg::Replay(ReplayMsg::Start))>{ "|<" }</button>
+                    <button onclick=link.callback(|_| Msg::Replay(ReplayMsg::Back))>{ "<" }</button>
+                    <button onclick=link.callback(|_| Msg::Replay(ReplayMsg::TogglePlay))>{ play_label }</button>
+                    <button onclick=link.callback(|_| Msg::Replay(ReplayMsg::Forward))>{ ">" }</button>
+                    <button onclick=link.callback(|_| Msg::Replay(ReplayMsg::End))>{ ">|" }</button>
+                </div>
+                <label>
+                    { "Speed " }
+                    <input
+                        type="number"
+                        min=1
+                        max=MAX_SPEED.to_string()
+                        value=self.speed.to_string()
+                        onchange=link.callback(|e: ChangeData| match e {
+                            ChangeData::Value(value) => {
+                                Msg::Replay(ReplayMsg::SetSpeed(value.parse().unwrap_or(DEFAULT_SPEED)))
+                            }
+                            _ => Msg::Replay(ReplayMsg::SetSpeed(DEFAULT_SPEED)),
+                        })
+                    />
+                    { " moves/s" }
+                </label>
+                <button onclick=link.callback(|_| Msg::Replay(ReplayMsg::Exit))>{ "Play from here" }</button>
+            </div>
+        }
+    }
+}
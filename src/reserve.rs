@@ -0,0 +1,93 @@
+use solitaire_web::card::Card;
+use yew::{html, Html};
+
+use crate::physical_card::{CardSource, CardVisual, PhysicalCard};
+
+/// A squared-up pile that cards can only be played off, one at a time from the top. The card
+/// underneath is turned face up as each one is taken.
+pub struct Reserve {
+    cards: Vec<PhysicalCard>,
+    x: i32,
+    y: i32,
+}
+
+impl Reserve {
+    pub fn new(x: i32, y: i32) -> Self {
+        Self {
+            cards: Vec::new(),
+            x,
+            y,
+        }
+    }
+
+    pub fn deal_card(&mut self, mut card: PhysicalCard, face_up: bool, animate: bool) {
+        if animate {
+            card.move_to(self.x, self.y);
+        } else {
+            card.set_position(self.x, self.y);
+        }
+        card.set_flipped(!face_up);
+        self.cards.push(card);
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        self.cards.iter().map(PhysicalCard::card).collect()
+    }
+
+    pub fn as_html(&self) -> Html {
+        let top_card_html = match self.cards.last() {
+            Some(card) => card.as_draggable_html(),
+            None => html! {},
+        };
+        // The card below only shows while the top one is being dragged off
+        let second_top_card_html = if self.cards.len() >= 2 {
+            self.cards[self.cards.len() - 2].as_html()
+        } else {
+            CardVisual::EmptySlot.as_html(self.x, self.y, String::new())
+        };
+
+        html! {
+            <>
+                { second_top_card_html }
+                { top_card_html }
+            </>
+        }
+    }
+}
+
+impl CardSource for Reserve {
+    fn take_cards(&mut self, num: usize) -> Vec<PhysicalCard> {
+        if num > 0 {
+            let cards = self.cards.pop().map_or_else(Vec::new, |c| vec![c]);
+            if let Some(new_top_card) = self.cards.last_mut() {
+                new_top_card.set_flipped(false);
+            }
+            cards
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn borrow_cards(&self, count: usize) -> Vec<&PhysicalCard> {
+        if count > 0 {
+            self.cards.last().map_or_else(Vec::new, |c| vec![c])
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn borrow_cards_mut(&mut self, count: usize) -> Vec<&mut PhysicalCard> {
+        if count > 0 {
+            self.cards.last_mut().map_or_else(Vec::new, |c| vec![c])
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn how_many_cards(&self, mouse_x: i32, mouse_y: i32) -> usize {
+        match self.cards.last() {
+            Some(card) if card.within_bounds(mouse_x, mouse_y) && !card.flipped() => 1,
+            _ => 0,
+        }
+    }
+}
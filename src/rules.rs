@@ -0,0 +1,114 @@
+use crate::card::{Card, Value};
+
+/// Whether a run of cards starting with `card` can be placed on a tableau whose top card is
+/// `top`. Runs go down in value and alternate in colour, and only a King can fill an empty
+/// tableau.
+pub fn builds_on_tableau(card: Card, top: Option<Card>) -> bool {
+    let Card(value, suit) = card;
+    match top {
+        Some(Card(top_value, top_suit)) => {
+            top_value.prev_value() == Some(value) && top_suit.colour() != suit.colour()
+        }
+        None => value == Value::King,
+    }
+}
+
+/// Like `builds_on_tableau`, but for runs that are built down in a single suit rather than in
+/// alternating colours.
+pub fn builds_on_tableau_in_suit(card: Card, top: Option<Card>) -> bool {
+    let Card(value, suit) = card;
+    match top {
+        Some(Card(top_value, top_suit)) => {
+            top_value.prev_value() == Some(value) && top_suit == suit
+        }
+        None => value == Value::King,
+    }
+}
+
+/// Whether `cards` go down in value and alternate in colour, so they could be moved together
+/// under the tableau rules.
+pub fn is_tableau_run(cards: &[Card]) -> bool {
+    cards
+        .windows(2)
+        .all(|pair| builds_on_tableau(pair[1], Some(pair[0])))
+}
+
+/// Whether each of `cards` is one lower than the card before it and in the same suit, so they
+/// could be moved together in Spider.
+pub fn is_suit_run(cards: &[Card]) -> bool {
+    cards.windows(2).all(|pair| {
+        let (Card(value, suit), Card(next_value, next_suit)) = (pair[0], pair[1]);
+        value.prev_value() == Some(next_value) && suit == next_suit
+    })
+}
+
+/// Whether `cards` end in a whole suit from King down to Ace, which leaves the board in Spider
+/// and Scorpion.
+pub fn ends_in_full_suit(cards: &[Card]) -> bool {
+    let run = match cards.len().checked_sub(13) {
+        Some(start) => &cards[start..],
+        None => return false,
+    };
+    matches!(run.first(), Some(Card(Value::King, _))) && is_suit_run(run)
+}
+
+/// Whether `cards` can be taken off the board together in Pyramid: a King on its own, or two
+/// cards whose values add up to 13.
+pub fn is_pyramid_pair(cards: &[Card]) -> bool {
+    match cards {
+        [Card(value, _)] => *value == Value::King,
+        [Card(value, _), Card(other_value, _)] => value.number() + other_value.number() == 13,
+        _ => false,
+    }
+}
+
+/// Whether `card` can be played onto the waste in TriPeaks and Golf, which takes a card one higher
+/// or one lower than `top`. With `wrap`, Kings and Aces are one apart as well.
+pub fn plays_onto_waste(card: Card, top: Option<Card>, wrap: bool) -> bool {
+    let Card(value, _) = card;
+    match top {
+        Some(Card(top_value, _)) if wrap => {
+            top_value.next_value_wrapping() == value || top_value.prev_value_wrapping() == value
+        }
+        Some(Card(top_value, _)) => {
+            top_value.next_value() == Some(value) || top_value.prev_value() == Some(value)
+        }
+        None => false,
+    }
+}
+
+/// Like `builds_on_tableau`, but a King can go on an Ace, and any card can fill an empty tableau,
+/// as in Canfield.
+pub fn builds_on_tableau_wrapping(card: Card, top: Option<Card>) -> bool {
+    let Card(value, suit) = card;
+    match top {
+        Some(Card(top_value, top_suit)) => {
+            top_value.prev_value_wrapping() == value && top_suit.colour() != suit.colour()
+        }
+        None => true,
+    }
+}
+
+/// Like `builds_on_foundation`, but foundations start from `base` rather than the Ace, and go
+/// round from King to Ace.
+pub fn builds_on_foundation_from(cards: &[Card], top: Option<Card>, base: Value) -> bool {
+    match (cards, top) {
+        ([Card(value, suit)], Some(Card(top_value, top_suit))) => {
+            top_suit == *suit && top_value.next_value_wrapping() == *value
+        }
+        ([Card(value, _)], None) => *value == base,
+        _ => false,
+    }
+}
+
+/// Whether `cards` can be placed on a foundation whose top card is `top`. Foundations take one
+/// card at a time, going up in value from the Ace in a single suit.
+pub fn builds_on_foundation(cards: &[Card], top: Option<Card>) -> bool {
+    match (cards, top) {
+        ([Card(value, suit)], Some(Card(top_value, top_suit))) => {
+            top_suit == *suit && top_value.next_value() == Some(*value)
+        }
+        ([Card(value, _)], None) => *value == Value::Ace,
+        _ => false,
+    }
+}
@@ -0,0 +1,28 @@
+use crate::moves::{Move, Pile};
+
+/// The score in games scored by streaks, as TriPeaks and Golf are. Each card played onto the
+/// waste scores one more than the one before it, until a card is drawn from the stock and the
+/// streak starts again.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StreakScore {
+    pub points: usize,
+    // Cards played onto the waste since the last draw
+    pub streak: usize,
+}
+
+impl StreakScore {
+    pub fn from_moves(moves: &[Move]) -> Self {
+        moves
+            .iter()
+            .fold(Self::default(), |score, game_move| match game_move {
+                Move::Transfer {
+                    to: Pile::Waste, ..
+                } => Self {
+                    points: score.points + score.streak + 1,
+                    streak: score.streak + 1,
+                },
+                Move::Draw | Move::Recycle => Self { streak: 0, ..score },
+                Move::Transfer { .. } => score,
+            })
+    }
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+
+use crate::{
+    moves::{Move, Pile},
+    options::Draw,
+    record::GameRecord,
+    variant::VariantKind,
+};
+
+// Bumped whenever the encoding changes, so old links can be told apart
+const VERSION: u8 = 2;
+// Links from before other games were added, which are always Klondike
+const KLONDIKE_VERSION: u8 = 1;
+
+const DRAW_ONE_FLAG: u8 = 1;
+
+// Stock moves use codes that no pair of piles can produce
+const DRAW_CODE: u8 = 0xF0;
+const RECYCLE_CODE: u8 = 0xF1;
+
+// In version 1 links, where piles are packed two to a byte
+const KLONDIKE_FOUNDATION_COUNT: usize = 4;
+const KLONDIKE_TABLEAU_COUNT: usize = 7;
+
+// In version 2 links, each pile has a byte with its kind in the top two bits
+const FOUNDATION_BITS: u8 = 0x40;
+const TABLEAU_BITS: u8 = 0x80;
+const CELL_BITS: u8 = 0xC0;
+const INDEX_MASK: u8 = 0x3F;
+// A waste is never numbered, so these can't be mistaken for piles
+const PILE_DRAW_CODE: u8 = 0x01;
+const PILE_RECYCLE_CODE: u8 = 0x02;
+// Like the waste, there's only one reserve
+const RESERVE_CODE: u8 = 0x03;
+// The waste's kind has room to spare, so places in a pyramid use the top half of it
+const PYRAMID_BITS: u8 = 0x20;
+const PYRAMID_INDEX_MASK: u8 = 0x1F;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ShareError {
+    NotBase64,
+    UnsupportedVersion(u8),
+    TooShort,
+    UnknownGame(u8),
+    // Moves are numbered from 1
+    InvalidMove(usize),
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareError::NotBase64 => write!(f, "the game code is not valid base64url"),
+            ShareError::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "the game code is from an unsupported version ({})",
+                    version
+                )
+            }
+            ShareError::TooShort => write!(f, "the game code is too short"),
+            ShareError::UnknownGame(game) => {
+                write!(f, "the game code is for an unknown game ({})", game)
+            }
+            ShareError::InvalidMove(number) => {
+                write!(f, "move {} in the game code is not valid", number)
+            }
+        }
+    }
+}
+
+fn pile_code(pile: Pile) -> u8 {
+    match pile {
+        Pile::Waste => 0,
+        Pile::Foundation(index) => FOUNDATION_BITS | index as u8,
+        Pile::Tableau(index) => TABLEAU_BITS | index as u8,
+        Pile::Cell(index) => CELL_BITS | index as u8,
+        Pile::Pyramid(index) => PYRAMID_BITS | index as u8,
+        Pile::Reserve => RESERVE_CODE,
+    }
+}
+
+fn code_pile(code: u8) -> Option<Pile> {
+    let index = (code & INDEX_MASK) as usize;
+    match code & !INDEX_MASK {
+        0 if index == 0 => Some(Pile::Waste),
+        0 if code == RESERVE_CODE => Some(Pile::Reserve),
+        0 if code & PYRAMID_BITS != 0 => Some(Pile::Pyramid((code & PYRAMID_INDEX_MASK) as usize)),
+        FOUNDATION_BITS => Some(Pile::Foundation(index)),
+        TABLEAU_BITS => Some(Pile::Tableau(index)),
+        CELL_BITS => Some(Pile::Cell(index)),
+        _ => None,
+    }
+}
+
+fn klondike_code_pile(code: u8) -> Option<Pile> {
+    let code = code as usize;
+    if code == 0 {
+        Some(Pile::Waste)
+    } else if code <= KLONDIKE_FOUNDATION_COUNT {
+        Some(Pile::Foundation(code - 1))
+    } else if code <= KLONDIKE_FOUNDATION_COUNT + KLONDIKE_TABLEAU_COUNT {
+        Some(Pile::Tableau(code - 1 - KLONDIKE_FOUNDATION_COUNT))
+    } else {
+        None
+    }
+}
+
+/// Packs a game record into a short base64url code for a share link.
+///
+/// The code is a version byte, a flags byte, a byte for the game played, the seed as 8 big-endian
+/// bytes, then the moves. A draw or recycle is a single byte, and other moves are a byte for the
+/// source pile and one for the destination, with the kind of pile in the top two bits and its
+/// number in the rest (places in a pyramid are 0x20 plus their number). Moves from a tableau are
+/// followed by a byte for the number of cards moved.
+pub fn encode(record: &GameRecord) -> String {
+    let flags = if record.draw == Draw::One {
+        DRAW_ONE_FLAG
+    } else {
+        0
+    };
+    let variant = VariantKind::ALL
+        .iter()
+        .position(|kind| *kind == record.variant)
+        .expect("every game should be listed") as u8;
+
+    let mut bytes = vec![VERSION, flags, variant];
+    bytes.extend_from_slice(&record.seed.to_be_bytes());
+
+    for game_move in record.moves.iter() {
+        match *game_move {
+            Move::Draw => bytes.push(PILE_DRAW_CODE),
+            Move::Recycle => bytes.push(PILE_RECYCLE_CODE),
+            Move::Transfer { from, count, to } => {
+                bytes.push(pile_code(from));
+                bytes.push(pile_code(to));
+                if let Pile::Tableau(_) = from {
+                    bytes.push(count as u8);
+                }
+            }
+        }
+    }
+
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+// Version 1 moves: stock moves have their own codes, and other moves pack the source pile into
+// the high nibble and the destination into the low nibble
+fn decode_klondike_moves(bytes: &[u8]) -> Result<Vec<Move>, ShareError> {
+    let mut moves = Vec::new();
+    let mut move_bytes = bytes.iter().copied();
+    while let Some(byte) = move_bytes.next() {
+        let invalid_move = ShareError::InvalidMove(moves.len() + 1);
+        let game_move = match byte {
+            DRAW_CODE => Move::Draw,
+            RECYCLE_CODE => Move::Recycle,
+            _ => {
+                let from = klondike_code_pile(byte >> 4).ok_or_else(|| invalid_move.clone())?;
+                let to = klondike_code_pile(byte & 0x0F).ok_or_else(|| invalid_move.clone())?;
+                let count = match from {
+                    Pile::Tableau(_) => move_bytes.next().ok_or(invalid_move)? as usize,
+                    _ => 1,
+                };
+                Move::Transfer { from, count, to }
+            }
+        };
+        moves.push(game_move);
+    }
+    Ok(moves)
+}
+
+fn decode_moves(bytes: &[u8]) -> Result<Vec<Move>, ShareError> {
+    let mut moves = Vec::new();
+    let mut move_bytes = bytes.iter().copied();
+    while let Some(byte) = move_bytes.next() {
+        let invalid_move = ShareError::InvalidMove(moves.len() + 1);
+        let game_move = match byte {
+            PILE_DRAW_CODE => Move::Draw,
+            PILE_RECYCLE_CODE => Move::Recycle,
+            _ => {
+                let from = code_pile(byte).ok_or_else(|| invalid_move.clone())?;
+                let to = move_bytes
+                    .next()
+                    .and_then(code_pile)
+                    .ok_or_else(|| invalid_move.clone())?;
+                let count = match from {
+                    Pile::Tableau(_) => move_bytes.next().ok_or(invalid_move)? as usize,
+                    _ => 1,
+                };
+                Move::Transfer { from, count, to }
+            }
+        };
+        moves.push(game_move);
+    }
+    Ok(moves)
+}
+
+pub fn decode(code: &str) -> Result<GameRecord, ShareError> {
+    let bytes =
+        base64::decode_config(code, base64::URL_SAFE_NO_PAD).map_err(|_| ShareError::NotBase64)?;
+
+    let version = *bytes.first().ok_or(ShareError::TooShort)?;
+    // Version 1 links have no byte for the game
+    let header_len = match version {
+        VERSION => 11,
+        KLONDIKE_VERSION => 10,
+        _ => return Err(ShareError::UnsupportedVersion(version)),
+    };
+    if bytes.len() < header_len {
+        return Err(ShareError::TooShort);
+    }
+
+    let draw = if bytes[1] & DRAW_ONE_FLAG != 0 {
+        Draw::One
+    } else {
+        Draw::Three
+    };
+    let variant = if version == VERSION {
+        *VariantKind::ALL
+            .get(bytes[2] as usize)
+            .ok_or(ShareError::UnknownGame(bytes[2]))?
+    } else {
+        VariantKind::Klondike
+    };
+    let mut seed = [0; 8];
+    seed.copy_from_slice(&bytes[header_len - 8..header_len]);
+
+    let moves = if version == VERSION {
+        decode_moves(&bytes[header_len..])?
+    } else {
+        decode_klondike_moves(&bytes[header_len..])?
+    };
+
+    Ok(GameRecord {
+        variant,
+        seed: u64::from_be_bytes(seed),
+        draw,
+        moves,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(from: Pile, count: usize, to: Pile) -> Move {
+        Move::Transfer { from, count, to }
+    }
+
+    #[test]
+    fn every_pile_round_trips() {
+        let record = GameRecord {
+            variant: VariantKind::Canfield,
+            seed: 0x0123_4567_89AB_CDEF,
+            draw: Draw::One,
+            moves: vec![
+                Move::Draw,
+                transfer(Pile::Waste, 1, Pile::Foundation(7)),
+                transfer(Pile::Tableau(12), 5, Pile::Tableau(0)),
+                transfer(Pile::Tableau(3), 1, Pile::Cell(3)),
+                transfer(Pile::Cell(0), 1, Pile::Foundation(0)),
+                transfer(Pile::Reserve, 1, Pile::Tableau(9)),
+                transfer(Pile::Pyramid(0), 1, Pile::Pyramid(27)),
+                transfer(Pile::Foundation(2), 1, Pile::Waste),
+                Move::Recycle,
+            ],
+        };
+
+        let decoded = decode(&encode(&record)).unwrap();
+        assert_eq!(decoded.variant, record.variant);
+        assert_eq!(decoded.seed, record.seed);
+        assert_eq!(decoded.draw, record.draw);
+        assert_eq!(decoded.moves, record.moves);
+    }
+
+    #[test]
+    fn every_game_round_trips() {
+        for variant in VariantKind::ALL.iter().copied() {
+            let record = GameRecord {
+                variant,
+                seed: 42,
+                draw: Draw::Three,
+                moves: Vec::new(),
+            };
+            assert_eq!(decode(&encode(&record)).unwrap().variant, variant);
+        }
+    }
+
+    #[test]
+    fn klondike_link_decodes() {
+        let mut bytes = vec![KLONDIKE_VERSION, DRAW_ONE_FLAG];
+        bytes.extend_from_slice(&1234u64.to_be_bytes());
+        // Draw, waste to tableau 1, three cards from tableau 3 to tableau 7, tableau 2 to
+        // foundation 1, recycle
+        bytes.extend_from_slice(&[DRAW_CODE, 0x05, 0x7B, 3, 0x61, 1, RECYCLE_CODE]);
+        let code = base64::encode_config(bytes, base64::URL_SAFE_NO_PAD);
+
+        let record = decode(&code).unwrap();
+        assert_eq!(record.variant, VariantKind::Klondike);
+        assert_eq!(record.seed, 1234);
+        assert_eq!(record.draw, Draw::One);
+        assert_eq!(
+            record.moves,
+            vec![
+                Move::Draw,
+                transfer(Pile::Waste, 1, Pile::Tableau(0)),
+                transfer(Pile::Tableau(2), 3, Pile::Tableau(6)),
+                transfer(Pile::Tableau(1), 1, Pile::Foundation(0)),
+                Move::Recycle,
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_codes_are_rejected() {
+        assert_eq!(decode("not base64!").unwrap_err(), ShareError::NotBase64);
+        let short = base64::encode_config([VERSION, 0, 0], base64::URL_SAFE_NO_PAD);
+        assert_eq!(decode(&short).unwrap_err(), ShareError::TooShort);
+
+        let mut bytes = vec![VERSION, 0, VariantKind::ALL.len() as u8];
+        bytes.extend_from_slice(&0u64.to_be_bytes());
+        let unknown = base64::encode_config(bytes, base64::URL_SAFE_NO_PAD);
+        assert_eq!(
+            decode(&unknown).unwrap_err(),
+            ShareError::UnknownGame(VariantKind::ALL.len() as u8)
+        );
+    }
+}
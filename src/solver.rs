@@ -0,0 +1,877 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    card::{Card, Suit, SuitColour, DECK},
+    game::Game,
+    moves::{Move, Pile},
+    options::Draw,
+    rules,
+    variant::{PileKind, Variant, KLONDIKE_FOUNDATIONS, KLONDIKE_TABLEAUS},
+};
+
+const KING: u8 = 13;
+
+/// Positions searched before a deal is given up on as too hard to decide.
+pub const MAX_NODES: usize = 200_000;
+
+/// Positions `OpenSolver` searches before giving up. Deals with every card face up have far more
+/// moves to choose from, and each position is cheap to search.
+pub const OPEN_MAX_NODES: usize = 2_000_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolveResult {
+    Solved(Vec<Move>),
+    // The search only tries moves that could help, so this means no win was found among those
+    Unsolvable,
+    // The search ran out of nodes before finding an answer
+    GaveUp,
+}
+
+/// Figures from solving a deal, used to judge how hard it is.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolveStats {
+    // The length of the solution found, which isn't necessarily the shortest
+    pub solution_length: usize,
+    // Positions searched before the solution was found
+    pub nodes: usize,
+    // Cards lying on top of face-down aces and twos, which have to be uncovered early
+    pub buried_cards: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct SolverCard {
+    // Ace is 1 and King is 13
+    rank: u8,
+    suit: u8,
+    red: bool,
+}
+
+impl From<Card> for SolverCard {
+    fn from(Card(value, suit): Card) -> Self {
+        Self {
+            rank: value as u8 + 1,
+            suit: suit as u8,
+            red: suit.colour() == SuitColour::Red,
+        }
+    }
+}
+
+// Matches the order of `Suit`: spades, clubs, diamonds, hearts
+fn is_red(suit: u8) -> bool {
+    suit >= 2
+}
+
+// So the search can be checked against the same rules as the game
+impl From<SolverCard> for Card {
+    fn from(card: SolverCard) -> Self {
+        // In the order of `Suit`, and the first suit in the deck runs from Ace to King
+        const SUITS: [Suit; 4] = [Suit::Spades, Suit::Clubs, Suit::Diamonds, Suit::Hearts];
+        let Card(value, _) = DECK[card.rank as usize - 1];
+        Card(value, SUITS[card.suit as usize])
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+struct Column {
+    cards: Vec<SolverCard>,
+    // The first `face_down` cards are face down
+    face_down: usize,
+}
+
+impl Column {
+    fn face_up(&self) -> &[SolverCard] {
+        &self.cards[self.face_down..]
+    }
+
+    fn accepts(&self, card: &SolverCard) -> bool {
+        let top = self.cards.last().map(|top| Card::from(*top));
+        rules::builds_on_tableau(Card::from(*card), top)
+    }
+}
+
+/// A Klondike position, kept as simple as possible so it is cheap to copy while searching.
+#[derive(Clone)]
+struct State {
+    // The top of the stock and waste are at the end
+    stock: Vec<SolverCard>,
+    waste: Vec<SolverCard>,
+    foundations: [Option<SolverCard>; KLONDIKE_FOUNDATIONS],
+    columns: [Column; KLONDIKE_TABLEAUS],
+}
+
+impl State {
+    // Copies a position from the game, so solutions can be played on the real board
+    fn from_game(game: &Game) -> Self {
+        let cards = |cards: &[Card]| cards.iter().copied().map(SolverCard::from).collect();
+        let mut columns: [Column; KLONDIKE_TABLEAUS] = Default::default();
+        for (column, tableau) in columns.iter_mut().zip(game.tableaus()) {
+            column.cards = cards(tableau.cards());
+            column.face_down = tableau.face_down();
+        }
+
+        Self {
+            stock: cards(game.stock()),
+            waste: cards(game.waste()),
+            foundations: game
+                .foundations()
+                .clone()
+                .map(|f| f.last().copied().map(SolverCard::from)),
+            columns,
+        }
+    }
+
+    fn buried_cards(&self) -> usize {
+        self.columns
+            .iter()
+            .map(|column| {
+                column.cards[..column.face_down]
+                    .iter()
+                    .position(|c| c.rank <= 2)
+                    .map_or(0, |i| column.cards.len() - i - 1)
+            })
+            .sum()
+    }
+
+    fn is_won(&self) -> bool {
+        self.foundations
+            .iter()
+            .all(|f| f.is_some_and(|c| c.rank == KING))
+    }
+
+    fn foundation_rank(&self, suit: u8) -> u8 {
+        self.foundations
+            .iter()
+            .flatten()
+            .find(|c| c.suit == suit)
+            .map_or(0, |c| c.rank)
+    }
+
+    // The foundation the card can be placed on, if any
+    fn foundation_for(&self, card: &SolverCard) -> Option<usize> {
+        let cards = [Card::from(*card)];
+        self.foundations
+            .iter()
+            .position(|f| rules::builds_on_foundation(&cards, f.map(Card::from)))
+    }
+
+    // A card is safe to put on a foundation when every card that could be placed on it in a
+    // tableau is already on the foundations, so there's no reason to keep it back.
+    fn is_safe_for_foundation(&self, card: &SolverCard) -> bool {
+        (0..KLONDIKE_FOUNDATIONS as u8)
+            .filter(|&suit| is_red(suit) != card.red)
+            .all(|suit| self.foundation_rank(suit) + 1 >= card.rank)
+    }
+
+    fn top_cards(&self) -> impl Iterator<Item = (Pile, SolverCard)> + '_ {
+        let waste = self.waste.last().map(|c| (Pile::Waste, *c));
+        let columns = self
+            .columns
+            .iter()
+            .enumerate()
+            .filter_map(|(i, column)| column.cards.last().map(|c| (Pile::Tableau(i), *c)));
+        waste.into_iter().chain(columns)
+    }
+
+    /// Lists the moves worth trying, with the most promising first.
+    fn moves(&self) -> Vec<Move> {
+        let mut foundation_moves = Vec::new();
+        for (from, card) in self.top_cards() {
+            if let Some(to) = self.foundation_for(&card) {
+                let game_move = Move::Transfer {
+                    from,
+                    count: 1,
+                    to: Pile::Foundation(to),
+                };
+                // There's never a reason to try anything else first
+                if self.is_safe_for_foundation(&card) {
+                    return vec![game_move];
+                }
+                foundation_moves.push(game_move);
+            }
+        }
+
+        let first_empty = self.columns.iter().position(|c| c.cards.is_empty());
+        let mut revealing_moves = Vec::new();
+        let mut other_moves = Vec::new();
+
+        for (from, column) in self.columns.iter().enumerate() {
+            let face_up = column.face_up();
+            for (i, card) in face_up.iter().enumerate() {
+                let count = face_up.len() - i;
+                let whole_run = i == 0;
+                // Splitting a run is only useful if it lets the card left behind go up
+                if !whole_run && self.foundation_for(&face_up[i - 1]).is_none() {
+                    continue;
+                }
+
+                for (to, target) in self.columns.iter().enumerate() {
+                    if to == from || !target.accepts(card) {
+                        continue;
+                    }
+                    if target.cards.is_empty() {
+                        // Every empty column is the same, and moving a King that is already at
+                        // the bottom of its column changes nothing
+                        if Some(to) != first_empty || (whole_run && column.face_down == 0) {
+                            continue;
+                        }
+                    }
+
+                    let game_move = Move::Transfer {
+                        from: Pile::Tableau(from),
+                        count,
+                        to: Pile::Tableau(to),
+                    };
+                    if whole_run && column.face_down > 0 {
+                        revealing_moves.push((column.face_down, game_move));
+                    } else {
+                        other_moves.push(game_move);
+                    }
+                }
+            }
+        }
+        // Uncover the longest face-down piles first
+        revealing_moves.sort_by_key(|(face_down, _)| std::cmp::Reverse(*face_down));
+
+        let mut waste_moves = Vec::new();
+        if let Some(card) = self.waste.last() {
+            for (to, target) in self.columns.iter().enumerate() {
+                if target.accepts(card) && (!target.cards.is_empty() || Some(to) == first_empty) {
+                    waste_moves.push(Move::Transfer {
+                        from: Pile::Waste,
+                        count: 1,
+                        to: Pile::Tableau(to),
+                    });
+                }
+            }
+        }
+
+        let stock_move = if !self.stock.is_empty() {
+            Some(Move::Draw)
+        } else if !self.waste.is_empty() {
+            Some(Move::Recycle)
+        } else {
+            None
+        };
+
+        let mut foundation_returns = Vec::new();
+        for (from, top) in self.foundations.iter().enumerate() {
+            if let Some(card) = top {
+                for (to, target) in self.columns.iter().enumerate() {
+                    if !target.cards.is_empty() && target.accepts(card) {
+                        foundation_returns.push(Move::Transfer {
+                            from: Pile::Foundation(from),
+                            count: 1,
+                            to: Pile::Tableau(to),
+                        });
+                    }
+                }
+            }
+        }
+
+        foundation_moves
+            .into_iter()
+            .chain(revealing_moves.into_iter().map(|(_, m)| m))
+            .chain(waste_moves)
+            .chain(other_moves)
+            .chain(stock_move)
+            .chain(foundation_returns)
+            .collect()
+    }
+
+    // Only called with moves from `moves`, so they are known to be legal
+    fn apply(&mut self, game_move: Move, draw: Draw) {
+        match game_move {
+            Move::Draw => {
+                for _ in 0..draw.count() {
+                    if let Some(card) = self.stock.pop() {
+                        self.waste.push(card);
+                    }
+                }
+            }
+            Move::Recycle => {
+                self.stock = self.waste.drain(..).rev().collect();
+            }
+            Move::Transfer { from, count, to } => {
+                let cards = match from {
+                    Pile::Waste => self.waste.pop().into_iter().collect(),
+                    Pile::Foundation(index) => {
+                        let card = self.foundations[index].expect("foundation should have a card");
+                        self.foundations[index] = Some(SolverCard {
+                            rank: card.rank - 1,
+                            ..card
+                        })
+                        .filter(|c| c.rank > 0);
+                        vec![card]
+                    }
+                    Pile::Tableau(index) => {
+                        let column = &mut self.columns[index];
+                        let cards = column.cards.split_off(column.cards.len() - count);
+                        if column.face_down > 0 && column.face_down == column.cards.len() {
+                            column.face_down -= 1;
+                        }
+                        cards
+                    }
+                    Pile::Cell(_) | Pile::Pyramid(_) | Pile::Reserve => {
+                        unreachable!("Klondike has no cells, pyramid or reserve")
+                    }
+                };
+
+                match to {
+                    Pile::Foundation(index) => self.foundations[index] = cards.last().copied(),
+                    Pile::Tableau(index) => self.columns[index].cards.extend(cards),
+                    Pile::Waste => unreachable!("cards are never moved onto the waste"),
+                    Pile::Cell(_) | Pile::Pyramid(_) | Pile::Reserve => {
+                        unreachable!("Klondike has no cells, pyramid or reserve")
+                    }
+                }
+            }
+        }
+    }
+
+    // Positions that only differ by which column or foundation holds what are the same for
+    // solving, so those are sorted before hashing.
+    fn key(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.stock.hash(&mut hasher);
+        self.waste.hash(&mut hasher);
+        for suit in 0..KLONDIKE_FOUNDATIONS as u8 {
+            self.foundation_rank(suit).hash(&mut hasher);
+        }
+        let mut columns = self.columns.iter().collect::<Vec<_>>();
+        columns.sort();
+        columns.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+struct Frame {
+    state: State,
+    moves: Vec<Move>,
+    next: usize,
+}
+
+/// A depth-first search for a way to win a deal. The search can be run a few steps at a time,
+/// so it doesn't hold up the page.
+pub struct Solver {
+    draw: Draw,
+    stack: Vec<Frame>,
+    // The moves leading to the position at the top of the stack
+    path: Vec<Move>,
+    seen: HashSet<u64>,
+    nodes: usize,
+    max_nodes: usize,
+    buried_cards: usize,
+}
+
+impl Solver {
+    pub fn new(seed: u64, draw: Draw) -> Self {
+        Self::from_game(&Game::new(seed, draw))
+    }
+
+    /// Searches from the position the game is in.
+    pub fn from_game(game: &Game) -> Self {
+        let draw = game.draw();
+        let state = State::from_game(game);
+        let mut seen = HashSet::new();
+        seen.insert(state.key());
+
+        Self {
+            draw,
+            buried_cards: state.buried_cards(),
+            stack: vec![Frame {
+                moves: state.moves(),
+                state,
+                next: 0,
+            }],
+            path: Vec::new(),
+            seen,
+            nodes: 0,
+            max_nodes: MAX_NODES,
+        }
+    }
+
+    pub fn with_node_limit(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// How the search went, as far as it has got. Once a deal is solved, this describes the
+    /// solution.
+    pub fn stats(&self) -> SolveStats {
+        SolveStats {
+            solution_length: self.path.len(),
+            nodes: self.nodes,
+            buried_cards: self.buried_cards,
+        }
+    }
+
+    /// Searches up to `node_budget` more positions, returning the result once there is one.
+    pub fn step(&mut self, node_budget: usize) -> Option<SolveResult> {
+        for _ in 0..node_budget {
+            let frame = match self.stack.last_mut() {
+                Some(frame) => frame,
+                None => return Some(SolveResult::Unsolvable),
+            };
+
+            let game_move = match frame.moves.get(frame.next) {
+                Some(game_move) => *game_move,
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                    continue;
+                }
+            };
+            frame.next += 1;
+
+            let mut state = frame.state.clone();
+            state.apply(game_move, self.draw);
+            if !self.seen.insert(state.key()) {
+                continue;
+            }
+
+            self.nodes += 1;
+            self.path.push(game_move);
+            if state.is_won() {
+                return Some(SolveResult::Solved(self.path.clone()));
+            }
+            if self.nodes >= self.max_nodes {
+                return Some(SolveResult::GaveUp);
+            }
+
+            self.stack.push(Frame {
+                moves: state.moves(),
+                state,
+                next: 0,
+            });
+        }
+
+        None
+    }
+}
+
+// A move between two piles, by their index in the variant's list
+#[derive(Clone, Copy)]
+struct Transfer {
+    from: usize,
+    count: usize,
+    to: usize,
+}
+
+struct OpenFrame {
+    transfers: Vec<Transfer>,
+    next: usize,
+}
+
+/// A depth-first search for a way to win a deal that's dealt entirely face up, going by the
+/// variant's own rules rather than Klondike's. Nothing is left out but repeated positions and
+/// moves that couldn't do better than a safe move onto a foundation, so if the search finishes
+/// within its node limit its answer is exact either way. Otherwise it gives up without one.
+pub struct OpenSolver {
+    variant: Box<dyn Variant>,
+    kinds: Vec<PileKind>,
+    // The position at the top of the stack. Moves are played on it and taken back again, which
+    // is cheaper than keeping a copy for every frame.
+    piles: Vec<Vec<Card>>,
+    stack: Vec<OpenFrame>,
+    // The moves leading to the position at the top of the stack, to take back when the search
+    // backs up
+    played: Vec<Transfer>,
+    // The same moves in move notation
+    path: Vec<Move>,
+    seen: HashSet<u64>,
+    nodes: usize,
+    max_nodes: usize,
+}
+
+impl OpenSolver {
+    /// Searches from the deal from `seed`. None if the variant keeps cards hidden, in which case
+    /// there's no exact answer to give.
+    pub fn new(variant: Box<dyn Variant>, seed: u64) -> Option<Self> {
+        if !variant.all_cards_visible() {
+            return None;
+        }
+
+        let mut piles = vec![Vec::new(); variant.piles().len()];
+        for (pile, card, _) in variant.deal(seed).cards {
+            piles[pile].push(card);
+        }
+        Some(Self::from_piles(variant, piles))
+    }
+
+    // Searches from a position given as the cards in each of the variant's piles
+    fn from_piles(variant: Box<dyn Variant>, piles: Vec<Vec<Card>>) -> Self {
+        let kinds = variant.piles().iter().map(|spec| spec.kind).collect();
+        let mut solver = Self {
+            variant,
+            kinds,
+            piles,
+            stack: Vec::new(),
+            played: Vec::new(),
+            path: Vec::new(),
+            seen: HashSet::new(),
+            nodes: 0,
+            max_nodes: OPEN_MAX_NODES,
+        };
+        solver.seen.insert(solver.key(&solver.piles));
+        solver.stack.push(OpenFrame {
+            transfers: solver.transfers(&solver.piles),
+            next: 0,
+        });
+        solver
+    }
+
+    pub fn with_node_limit(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    fn is_open_pile(kind: PileKind) -> bool {
+        matches!(
+            kind,
+            PileKind::Foundation | PileKind::Tableau | PileKind::Cell
+        )
+    }
+
+    // A card is safe to put on a foundation once no card left in play could be built onto it,
+    // since it can only get in the way after that
+    fn is_safe_for_foundation(&self, piles: &[Vec<Card>], card: Card) -> bool {
+        self.kinds
+            .iter()
+            .zip(piles)
+            .filter(|(kind, _)| **kind != PileKind::Foundation)
+            .flat_map(|(_, cards)| cards)
+            .all(|other| {
+                !self
+                    .variant
+                    .builds_on(PileKind::Tableau, &[*other], Some(card))
+            })
+    }
+
+    // Every legal move, with moves onto the foundations first. A safe move onto a foundation is
+    // the only one worth trying when there is one.
+    fn transfers(&self, piles: &[Vec<Card>]) -> Vec<Transfer> {
+        let empty = |kind| {
+            self.kinds
+                .iter()
+                .zip(piles)
+                .filter(|(k, cards)| **k == kind && cards.is_empty())
+                .count()
+        };
+        let (empty_cells, empty_tableaus) = (empty(PileKind::Cell), empty(PileKind::Tableau));
+
+        let mut foundation_transfers = Vec::new();
+        let mut other_transfers = Vec::new();
+        for (from, cards) in piles.iter().enumerate() {
+            let from_kind = self.kinds[from];
+            // Cards put on a foundation stay there in every game with all its cards face up
+            if from_kind == PileKind::Foundation || !Self::is_open_pile(from_kind) {
+                continue;
+            }
+
+            for count in 1..=cards.len() {
+                let group = &cards[cards.len() - count..];
+                if !self.variant.is_movable_group(from_kind, group) {
+                    continue;
+                }
+
+                for (to, target) in piles.iter().enumerate() {
+                    let to_kind = self.kinds[to];
+                    if to == from || !Self::is_open_pile(to_kind) {
+                        continue;
+                    }
+                    // Moving a whole pile into an empty one of the same kind changes nothing
+                    if target.is_empty() && count == cards.len() && to_kind == from_kind {
+                        continue;
+                    }
+
+                    let empty_tableaus = if to_kind == PileKind::Tableau && target.is_empty() {
+                        empty_tableaus - 1
+                    } else {
+                        empty_tableaus
+                    };
+                    let capacity = self
+                        .variant
+                        .move_capacity(to_kind, empty_cells, empty_tableaus);
+                    if count > capacity
+                        || !self
+                            .variant
+                            .builds_on(to_kind, group, target.last().copied())
+                    {
+                        continue;
+                    }
+
+                    let transfer = Transfer { from, count, to };
+                    if to_kind == PileKind::Foundation {
+                        // There's never a reason to try anything else first
+                        if self.is_safe_for_foundation(piles, group[0]) {
+                            return vec![transfer];
+                        }
+                        foundation_transfers.push(transfer);
+                    } else {
+                        other_transfers.push(transfer);
+                    }
+                }
+            }
+        }
+
+        // Try the moves that leave the fewest cards in the way first. Only the two piles a move
+        // touches change, so the rest don't need counting again.
+        other_transfers.sort_by_cached_key(|transfer| {
+            let (from, to) = (&piles[transfer.from], &piles[transfer.to]);
+            let moved = &from[from.len() - transfer.count..];
+            let before = self.blocking_cards(transfer.from, from.iter())
+                + self.blocking_cards(transfer.to, to.iter());
+            let after = self
+                .blocking_cards(transfer.from, from[..from.len() - transfer.count].iter())
+                + self.blocking_cards(transfer.to, to.iter().chain(moved));
+            after as isize - before as isize
+        });
+
+        foundation_transfers
+            .into_iter()
+            .chain(other_transfers)
+            .collect()
+    }
+
+    // Cards in pile `index` that sit on top of a lower card, which has to go up to the
+    // foundations first. Cards in the way of the lowest cards count for the most.
+    fn blocking_cards<'a>(&self, index: usize, cards: impl Iterator<Item = &'a Card>) -> usize {
+        if self.kinds[index] == PileKind::Foundation {
+            return 0;
+        }
+
+        let mut lowest = KING;
+        cards
+            .map(|Card(value, _)| {
+                let rank = *value as u8 + 1;
+                let blocking = if rank > lowest {
+                    (KING + 1 - lowest) as usize
+                } else {
+                    0
+                };
+                lowest = lowest.min(rank);
+                blocking
+            })
+            .sum()
+    }
+
+    fn apply(piles: &mut [Vec<Card>], transfer: Transfer) {
+        let start = piles[transfer.from].len() - transfer.count;
+        for i in start..piles[transfer.from].len() {
+            let card = piles[transfer.from][i];
+            piles[transfer.to].push(card);
+        }
+        piles[transfer.from].truncate(start);
+    }
+
+    fn undo(piles: &mut [Vec<Card>], transfer: Transfer) {
+        let Transfer { from, count, to } = transfer;
+        Self::apply(
+            piles,
+            Transfer {
+                from: to,
+                count,
+                to: from,
+            },
+        );
+    }
+
+    // Piles of the same kind are interchangeable, so each pile is hashed on its own and the
+    // hashes are sorted before being hashed together
+    fn key(&self, piles: &[Vec<Card>]) -> u64 {
+        let mut by_kind = self
+            .kinds
+            .iter()
+            .zip(piles)
+            .map(|(kind, cards)| {
+                let mut hasher = DefaultHasher::new();
+                for Card(value, suit) in cards {
+                    (*value as u8, *suit as u8).hash(&mut hasher);
+                }
+                (*kind as u8, hasher.finish())
+            })
+            .collect::<Vec<_>>();
+        by_kind.sort_unstable();
+
+        let mut hasher = DefaultHasher::new();
+        by_kind.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn to_move(&self, transfer: Transfer) -> Move {
+        let pile = |index| {
+            self.variant
+                .pile_name(index)
+                .expect("piles moved between should have names")
+        };
+        Move::Transfer {
+            from: pile(transfer.from),
+            count: transfer.count,
+            to: pile(transfer.to),
+        }
+    }
+
+    /// Searches up to `node_budget` more positions, returning the result once there is one.
+    pub fn step(&mut self, node_budget: usize) -> Option<SolveResult> {
+        for _ in 0..node_budget {
+            let frame = match self.stack.last_mut() {
+                Some(frame) => frame,
+                None => return Some(SolveResult::Unsolvable),
+            };
+
+            let transfer = match frame.transfers.get(frame.next) {
+                Some(transfer) => *transfer,
+                None => {
+                    self.stack.pop();
+                    self.path.pop();
+                    if let Some(transfer) = self.played.pop() {
+                        Self::undo(&mut self.piles, transfer);
+                    }
+                    continue;
+                }
+            };
+            frame.next += 1;
+
+            Self::apply(&mut self.piles, transfer);
+            if !self.seen.insert(self.key(&self.piles)) {
+                Self::undo(&mut self.piles, transfer);
+                continue;
+            }
+
+            self.nodes += 1;
+            self.played.push(transfer);
+            self.path.push(self.to_move(transfer));
+            if self.variant.is_won(&self.piles) {
+                return Some(SolveResult::Solved(self.path.clone()));
+            }
+            if self.nodes >= self.max_nodes {
+                return Some(SolveResult::GaveUp);
+            }
+
+            self.stack.push(OpenFrame {
+                transfers: self.transfers(&self.piles),
+                next: 0,
+            });
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        card::Value,
+        variant::{BakersDozen, FreeCell, VariantKind},
+    };
+
+    const FREECELL_FOUNDATIONS: usize = 4;
+    const FREECELL_TABLEAUS: usize = 8;
+
+    fn suit(suit: Suit) -> impl Iterator<Item = Card> {
+        DECK.iter().copied().filter(move |Card(_, s)| *s == suit)
+    }
+
+    // An empty FreeCell board, to put cards on: foundations, then tableaus, then cells
+    fn freecell_piles() -> Vec<Vec<Card>> {
+        vec![Vec::new(); FreeCell.piles().len()]
+    }
+
+    #[test]
+    fn solves_winnable_deals() {
+        let mut solver = OpenSolver::new(Box::new(FreeCell), 1).unwrap();
+        let result = solver.step(usize::MAX);
+        assert!(matches!(result, Some(SolveResult::Solved(_))));
+
+        let mut solver = OpenSolver::new(Box::new(BakersDozen), 1).unwrap();
+        let result = solver.step(usize::MAX);
+        assert!(matches!(result, Some(SolveResult::Solved(_))));
+    }
+
+    #[test]
+    fn keeps_hidden_deals_to_the_klondike_solver() {
+        let klondike = VariantKind::Klondike.rules(Draw::Three);
+        assert!(OpenSolver::new(klondike, 1).is_none());
+    }
+
+    #[test]
+    fn finds_a_stuck_position_unsolvable() {
+        // Everything is up but the last two hearts, and the King is on top of the Queen. Empty
+        // columns can't be filled in Baker's Dozen, so the King has nowhere to go.
+        let mut piles = vec![Vec::new(); BakersDozen.piles().len()];
+        piles[0] = suit(Suit::Spades).collect();
+        piles[1] = suit(Suit::Clubs).collect();
+        piles[2] = suit(Suit::Diamonds).collect();
+        piles[3] = suit(Suit::Hearts).take(11).collect();
+        piles[4] = vec![
+            Card(Value::Queen, Suit::Hearts),
+            Card(Value::King, Suit::Hearts),
+        ];
+
+        let mut solver = OpenSolver::from_piles(Box::new(BakersDozen), piles);
+        assert_eq!(solver.step(usize::MAX), Some(SolveResult::Unsolvable));
+    }
+
+    #[test]
+    fn only_plays_cards_up_once_nothing_can_build_on_them() {
+        let two_of_hearts = Card(Value::Two, Suit::Hearts);
+        let ace_of_spades = Card(Value::Ace, Suit::Spades);
+        let ace_of_diamonds = Card(Value::Ace, Suit::Diamonds);
+
+        // A black Ace could still go on the two of hearts
+        let mut piles = freecell_piles();
+        piles[FREECELL_FOUNDATIONS] = vec![ace_of_spades];
+        let solver = OpenSolver::from_piles(Box::new(FreeCell), piles.clone());
+        assert!(!solver.is_safe_for_foundation(&piles, two_of_hearts));
+
+        // A red one can't
+        piles[FREECELL_FOUNDATIONS] = vec![ace_of_diamonds];
+        assert!(solver.is_safe_for_foundation(&piles, two_of_hearts));
+
+        // Nor can a black Ace already on the foundations
+        piles[0] = vec![ace_of_spades];
+        piles[FREECELL_FOUNDATIONS] = Vec::new();
+        assert!(solver.is_safe_for_foundation(&piles, two_of_hearts));
+
+        // And nothing ever goes on an Ace
+        assert!(solver.is_safe_for_foundation(&piles, ace_of_spades));
+    }
+
+    #[test]
+    fn leaves_cards_on_the_foundations() {
+        let mut piles = freecell_piles();
+        piles[0] = vec![Card(Value::Ace, Suit::Spades)];
+        piles[FREECELL_FOUNDATIONS] = vec![Card(Value::Two, Suit::Hearts)];
+        let solver = OpenSolver::from_piles(Box::new(FreeCell), piles.clone());
+
+        let transfers = solver.transfers(&piles);
+        assert!(!transfers.is_empty());
+        assert!(transfers.iter().all(|transfer| transfer.from != 0));
+    }
+
+    #[test]
+    fn piles_of_the_same_kind_are_interchangeable() {
+        let cell = FREECELL_FOUNDATIONS + FREECELL_TABLEAUS;
+        let mut piles = freecell_piles();
+        piles[0] = vec![Card(Value::Ace, Suit::Spades)];
+        piles[FREECELL_FOUNDATIONS] = vec![Card(Value::King, Suit::Hearts)];
+        piles[FREECELL_FOUNDATIONS + 1] = vec![Card(Value::Five, Suit::Clubs)];
+        piles[cell] = vec![Card(Value::Two, Suit::Diamonds)];
+        let solver = OpenSolver::from_piles(Box::new(FreeCell), piles.clone());
+
+        let mut swapped = piles.clone();
+        swapped.swap(0, 3);
+        swapped.swap(FREECELL_FOUNDATIONS, FREECELL_FOUNDATIONS + 5);
+        swapped.swap(cell, cell + 2);
+        assert_eq!(solver.key(&piles), solver.key(&swapped));
+
+        // Moving a card to a pile of another kind is a different position
+        let mut moved = piles.clone();
+        moved.swap(FREECELL_FOUNDATIONS + 1, cell + 1);
+        assert_ne!(solver.key(&piles), solver.key(&moved));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{fmt, str::FromStr};
+
+use solitaire_web::variant::VariantKind;
+use yew::{html, ComponentLink, Html};
+
+use crate::{
+    util::{format_time, local_storage},
+    Model, Msg,
+};
+
+const STORAGE_KEY_PREFIX: &str = "solitaire-stats-";
+
+/// How many games of one variant have been played and won in this browser.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VariantStats {
+    pub played: u32,
+    pub won: u32,
+    // The quickest win, in seconds
+    pub best_seconds: Option<u32>,
+}
+
+impl VariantStats {
+    pub fn load(variant: VariantKind) -> Self {
+        local_storage()
+            .and_then(|storage| storage.get_item(&storage_key(variant)).ok().flatten())
+            .and_then(|stats| stats.parse().ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, variant: VariantKind) {
+        if let Some(storage) = local_storage() {
+            // Storage can be full or disabled, in which case the game just isn't counted
+            let _ = storage.set_item(&storage_key(variant), &self.to_string());
+        }
+    }
+
+    /// Counts a game as played, once the first move has been made in it.
+    pub fn record_played(variant: VariantKind) {
+        let mut stats = Self::load(variant);
+        stats.played += 1;
+        stats.save(variant);
+    }
+
+    pub fn record_won(variant: VariantKind, seconds: u32) {
+        let mut stats = Self::load(variant);
+        stats.won += 1;
+        stats.best_seconds = Some(stats.best_seconds.map_or(seconds, |best| best.min(seconds)));
+        stats.save(variant);
+    }
+}
+
+// Stored as e.g. "12 5 312": games played, games won and the quickest win in seconds, with "-"
+// for no wins yet
+impl fmt::Display for VariantStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.best_seconds {
+            Some(seconds) => write!(f, "{} {} {}", self.played, self.won, seconds),
+            None => write!(f, "{} {} -", self.played, self.won),
+        }
+    }
+}
+
+impl FromStr for VariantStats {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        let played = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        let won = parts.next().ok_or(())?.parse().map_err(|_| ())?;
+        let best_seconds = match parts.next() {
+            Some("-") => None,
+            Some(seconds) => Some(seconds.parse().map_err(|_| ())?),
+            None => return Err(()),
+        };
+
+        Ok(Self {
+            played,
+            won,
+            best_seconds,
+        })
+    }
+}
+
+fn storage_key(variant: VariantKind) -> String {
+    format!("{}{}", STORAGE_KEY_PREFIX, variant)
+}
+
+/// A table of the statistics for every variant.
+pub fn stats_html(link: &ComponentLink<Model>) -> Html {
+    let row_html = |variant: &VariantKind| {
+        let stats = VariantStats::load(*variant);
+        let win_rate = (stats.won * 100)
+            .checked_div(stats.played)
+            .map_or(String::from("-"), |rate| format!("{}%", rate));
+        let best_time = stats.best_seconds.map_or(String::from("-"), format_time);
+
+        html! {
+            <tr>
+                <td>{ variant }</td>
+                <td>{ stats.played }</td>
+                <td>{ stats.won }</td>
+                <td>{ win_rate }</td>
+                <td>{ best_time }</td>
+            </tr>
+        }
+    };
+
+    html! {
+        <div class="controls panel stats">
+            <table>
+                <tr>
+                    <th>{ "Game" }</th>
+                    <th>{ "Played" }</th>
+                    <th>{ "Won" }</th>
+                    <th>{ "Win rate" }</th>
+                    <th>{ "Best time" }</th>
+                </tr>
+                { for VariantKind::ALL.iter().map(row_html) }
+            </table>
+            <button onclick=link.callback(|_| Msg::ToggleStats)>{ "Close" }</button>
+        </div>
+    }
+}
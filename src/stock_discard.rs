@@ -1,11 +1,16 @@
-use std::cmp::max;
+use std::{cmp::max, rc::Rc};
 
+use solitaire_web::{
+    card::Card,
+    moves::Move,
+    variant::{PileKind, StockRules, Variant},
+};
 use yew::{html, Html};
 
 use crate::{
-    card::{CardSource, CardVisual, PhysicalCard},
+    physical_card::{CardSink, CardSource, CardVisual, PhysicalCard},
     util::Bounds,
-    CardSources, CARD_HEIGHT, CARD_WIDTH, CARD_X_STRIDE, STACKED_CARD_X_STRIDE,
+    CARD_HEIGHT, CARD_WIDTH, CARD_X_STRIDE, STACKED_CARD_X_STRIDE,
 };
 
 pub struct Stock {
@@ -49,10 +54,10 @@ impl Stock {
         }
     }
 
-    fn take_3_cards(&mut self) -> Vec<PhysicalCard> {
+    fn take_cards(&mut self, count: usize) -> Vec<PhysicalCard> {
         let mut cards: Vec<PhysicalCard> = Vec::new();
 
-        for _ in 0..3 {
+        for _ in 0..count {
             if let Some(card) = self.cards.pop() {
                 cards.push(card);
             }
@@ -82,21 +87,23 @@ impl Stock {
 }
 
 pub struct Discard {
+    variant: Rc<dyn Variant>,
     cards: Vec<PhysicalCard>,
     x: i32,
     y: i32,
 }
 
 impl Discard {
-    pub fn new(x: i32, y: i32) -> Self {
+    pub fn new(x: i32, y: i32, variant: Rc<dyn Variant>) -> Self {
         Self {
+            variant,
             cards: Vec::new(),
             x,
             y,
         }
     }
 
-    fn add_3_cards(&mut self, mut cards: Vec<PhysicalCard>) {
+    fn add_cards(&mut self, mut cards: Vec<PhysicalCard>) {
         self.cards.append(&mut cards);
 
         let len = self.cards.len();
@@ -142,6 +149,54 @@ impl Discard {
     }
 }
 
+// Most games only turn cards over onto the waste from the stock, but some let cards be played
+// onto it, and in others cards can be paired with its top card
+impl CardSink for Discard {
+    fn place_cards(
+        &mut self,
+        mouse_x: i32,
+        mouse_y: i32,
+        mut physical_cards: Vec<PhysicalCard>,
+    ) -> Result<(), ()> {
+        if self.is_placement_possible(
+            &physical_cards
+                .iter()
+                .map(PhysicalCard::card)
+                .collect::<Vec<Card>>(),
+        ) {
+            // The cards move on from wherever they were let go
+            physical_cards.iter_mut().for_each(|c| {
+                c.set_xy(
+                    mouse_x - CARD_WIDTH as i32 / 2,
+                    mouse_y - CARD_HEIGHT as i32 / 2,
+                );
+                c.set_visible(true);
+            });
+            self.add_cards(physical_cards);
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+
+    fn bounds(&self) -> Bounds {
+        let (x, y) = self.cards.last().map_or((self.x, self.y), |c| c.position());
+        Bounds::new(x, y, CARD_WIDTH, CARD_HEIGHT)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    fn is_placement_possible(&self, cards: &[Card]) -> bool {
+        self.variant.builds_on(
+            PileKind::Stock,
+            cards,
+            self.cards.last().map(PhysicalCard::card),
+        )
+    }
+}
+
 impl CardSource for Discard {
     fn take_cards(&mut self, num: usize) -> Vec<PhysicalCard> {
         if num > 0 {
@@ -169,10 +224,6 @@ impl CardSource for Discard {
         }
     }
 
-    fn card_source(&self) -> CardSources {
-        CardSources::Discard
-    }
-
     fn borrow_cards_mut(&mut self, count: usize) -> Vec<&mut PhysicalCard> {
         if count > 0 {
             self.cards.last_mut().map_or_else(Vec::new, |c| vec![c])
@@ -197,54 +248,110 @@ impl CardSource for Discard {
 pub struct StockDiscard {
     stock: Stock,
     discard: Discard,
+    rules: StockRules,
 }
 
 impl StockDiscard {
-    #[allow(dead_code)]
-    pub fn new(x: i32, y: i32) -> Self {
+    pub fn new(x: i32, y: i32, variant: Rc<dyn Variant>) -> Self {
         Self {
             stock: Stock::new(x, y),
-            discard: Discard::new(x + CARD_X_STRIDE, y),
+            rules: variant
+                .stock_rules()
+                .expect("a variant with a stock should have stock rules"),
+            discard: Discard::new(x + CARD_X_STRIDE, y, variant),
         }
     }
 
-    pub fn from_cards(x: i32, y: i32, cards: Vec<PhysicalCard>) -> Self {
+    pub fn from_cards(x: i32, y: i32, cards: Vec<PhysicalCard>, variant: Rc<dyn Variant>) -> Self {
         Self {
             stock: Stock::from_cards(x, y, cards),
-            discard: Discard::new(x + CARD_X_STRIDE, y),
+            ..Self::new(x, y, variant)
         }
     }
 
+    // Turns a card over onto the waste as part of the deal
+    pub fn deal_to_waste(&mut self, card: PhysicalCard) {
+        self.discard.add_cards(vec![card]);
+    }
+
+    pub fn discard(&self) -> &Discard {
+        &self.discard
+    }
+
     pub fn discard_mut(&mut self) -> &mut Discard {
         &mut self.discard
     }
 
-    pub fn handle_click(&mut self, x: i32, y: i32) -> bool {
-        if self.stock.within_bounds(x, y) {
-            self.deal_into_discard();
-            true
-        } else {
-            false
+    // The discard, unless the stock deals somewhere else and never uses it
+    pub fn waste(&self) -> Option<&Discard> {
+        match self.rules {
+            StockRules::Waste { .. } => Some(&self.discard),
+            StockRules::Tableaus { .. } => None,
         }
     }
 
-    pub fn deal_into_discard(&mut self) {
-        let cards = self.stock.take_3_cards();
+    pub fn waste_mut(&mut self) -> Option<&mut Discard> {
+        match self.rules {
+            StockRules::Waste { .. } => Some(&mut self.discard),
+            StockRules::Tableaus { .. } => None,
+        }
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        self.stock
+            .cards
+            .iter()
+            .chain(self.discard.cards.iter())
+            .map(PhysicalCard::card)
+            .collect()
+    }
+
+    pub fn stock_contains(&self, x: i32, y: i32) -> bool {
+        self.stock.within_bounds(x, y)
+    }
+
+    // Returns the move made, or None when the stock is empty and the discard can't be turned
+    // back over
+    pub fn deal_into_discard(&mut self) -> Option<Move> {
+        let (draw, recycle) = match self.rules {
+            StockRules::Waste { draw, recycle } => (draw, recycle),
+            StockRules::Tableaus { .. } => return None,
+        };
+        let cards = self.stock.take_cards(draw.count());
 
         if cards.is_empty() {
+            if !recycle {
+                return None;
+            }
+
             let mut cards = self.discard.take_cards();
+            if cards.is_empty() {
+                return None;
+            }
             cards.reverse();
             self.stock.deposit_cards(cards);
+            Some(Move::Recycle)
         } else {
-            self.discard.add_3_cards(cards);
+            self.discard.add_cards(cards);
+            Some(Move::Draw)
         }
     }
 
+    // Takes cards off the top of the stock to be dealt elsewhere, for stocks without a waste
+    pub fn take_from_stock(&mut self, count: usize) -> Vec<PhysicalCard> {
+        self.stock.take_cards(count)
+    }
+
     pub fn as_html(&self) -> Html {
+        let discard_html = match self.rules {
+            StockRules::Waste { .. } => self.discard.as_html(),
+            StockRules::Tableaus { .. } => html! {},
+        };
+
         html! {
             <>
             { self.stock.as_html() }
-            { self.discard.as_html() }
+            { discard_html }
             </>
         }
     }
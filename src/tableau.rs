@@ -1,51 +1,50 @@
-use std::cmp::max;
+use std::{cmp::max, rc::Rc};
 
+use solitaire_web::{
+    card::Card,
+    variant::{PileKind, Variant},
+};
 use yew::{html, Html};
 
 use crate::{
-    card::{Card, CardSink, CardSource, CardVisual, PhysicalCard, Value},
+    physical_card::{CardSink, CardSource, CardVisual, PhysicalCard},
     util::Bounds,
-    CardSinks, CardSources, CARD_HEIGHT, CARD_WIDTH, STACKED_CARD_Y_STRIDE,
+    CARD_HEIGHT, CARD_WIDTH, STACKED_CARD_Y_STRIDE,
 };
 
 pub struct Tableau {
     cards: Vec<PhysicalCard>,
-    sink: CardSinks,
-    source: CardSources,
+    variant: Rc<dyn Variant>,
     x: i32,
     y: i32,
 }
 
 impl Tableau {
-    pub fn new(x: i32, y: i32, sink: CardSinks, source: CardSources) -> Self {
+    pub fn new(x: i32, y: i32, variant: Rc<dyn Variant>) -> Self {
         Self {
             cards: Vec::new(),
-            sink,
-            source,
+            variant,
             x,
             y,
         }
     }
 
-    pub fn from_cards(
-        x: i32,
-        y: i32,
-        cards: Vec<PhysicalCard>,
-        sink: CardSinks,
-        source: CardSources,
-    ) -> Self {
-        let mut tableau = Tableau::new(x, y, sink, source);
-        tableau.cards = cards;
-        let len = tableau.cards.len();
-        tableau.cards.iter_mut().enumerate().for_each(|(i, card)| {
-            card.set_position(x, y + STACKED_CARD_Y_STRIDE * i as i32);
-            if i == len - 1 {
-                card.set_flipped(false);
-            } else {
-                card.set_flipped(true);
-            }
-        });
-        tableau
+    pub fn deal_card(&mut self, mut card: PhysicalCard, face_up: bool, animate: bool) {
+        let (x, y) = (
+            self.x,
+            self.y + STACKED_CARD_Y_STRIDE * self.cards.len() as i32,
+        );
+        if animate {
+            card.move_to(x, y);
+        } else {
+            card.set_position(x, y);
+        }
+        card.set_flipped(!face_up);
+        self.cards.push(card);
+    }
+
+    pub fn cards(&self) -> Vec<Card> {
+        self.cards.iter().map(PhysicalCard::card).collect()
     }
 
     pub fn as_html(&self) -> Html {
@@ -94,10 +93,6 @@ impl Tableau {
 }
 
 impl CardSource for Tableau {
-    fn card_source(&self) -> CardSources {
-        self.source
-    }
-
     fn borrow_cards(&self, count: usize) -> Vec<&PhysicalCard> {
         self.faceup_cards()
             .into_iter()
@@ -138,21 +133,32 @@ impl CardSource for Tableau {
         cards
     }
 
+    fn card_below(&self, count: usize) -> Option<&PhysicalCard> {
+        self.cards.iter().rev().nth(count)
+    }
+
     fn how_many_cards(&self, mouse_x: i32, mouse_y: i32) -> usize {
-        self.cards
+        let count = self
+            .cards
             .iter()
             .rev()
             .enumerate()
             .find(|(_, card)| card.within_bounds(mouse_x, mouse_y) && !card.flipped())
-            .map_or(0, |(i, _)| i + 1)
+            .map_or(0, |(i, _)| i + 1);
+
+        // Some variants only let cards be picked up together if they're in sequence
+        if self
+            .variant
+            .is_movable_group(PileKind::Tableau, &self.peek_cards(count))
+        {
+            count
+        } else {
+            0
+        }
     }
 }
 
 impl CardSink for Tableau {
-    fn card_sink(&self) -> CardSinks {
-        self.sink
-    }
-
     fn place_cards(
         &mut self,
         mouse_x: i32,
@@ -184,23 +190,24 @@ impl CardSink for Tableau {
         }
     }
 
-    fn within_bounds(&self, x: i32, y: i32) -> bool {
+    fn bounds(&self) -> Bounds {
         Bounds::new(
             self.x,
             self.y + STACKED_CARD_Y_STRIDE * max(self.cards.len() as i32 - 1, 0),
             CARD_WIDTH,
             CARD_HEIGHT,
         )
-        .contains(x, y)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.cards.is_empty()
     }
 
     fn is_placement_possible(&self, cards: &[Card]) -> bool {
-        let Card(value, suit) = *cards.first().expect("card should be present");
-        if let Some(Card(top_value, top_suit)) = self.cards.last().map(PhysicalCard::card) {
-            top_value.prev_value().map_or(false, |v| v == value)
-                && top_suit.colour() != suit.colour()
-        } else {
-            value == Value::King
-        }
+        self.variant.builds_on(
+            PileKind::Tableau,
+            cards,
+            self.cards.last().map(PhysicalCard::card),
+        )
     }
 }
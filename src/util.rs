@@ -1,3 +1,5 @@
+use std::cmp::{max, min};
+
 pub struct Bounds {
     pub x: i32,
     pub y: i32,
@@ -10,7 +12,62 @@ impl Bounds {
         Bounds { x, y, w, h }
     }
 
+    pub fn area(&self) -> u32 {
+        self.w * self.h
+    }
+
+    // The region covered by both bounds, if they overlap at all
+    pub fn intersection(&self, other: &Bounds) -> Option<Bounds> {
+        let left = max(self.x, other.x);
+        let top = max(self.y, other.y);
+        let right = min(self.x + self.w as i32, other.x + other.w as i32);
+        let bottom = min(self.y + self.h as i32, other.y + other.h as i32);
+
+        if left < right && top < bottom {
+            Some(Bounds::new(
+                left,
+                top,
+                (right - left) as u32,
+                (bottom - top) as u32,
+            ))
+        } else {
+            None
+        }
+    }
+
+    pub fn overlap_area(&self, other: &Bounds) -> u32 {
+        self.intersection(other).map_or(0, |b| b.area())
+    }
+
+    pub fn as_style(&self) -> String {
+        format!(
+            "left:{}px;top:{}px;width:{}px;height:{}px;",
+            self.x, self.y, self.w, self.h
+        )
+    }
+
     pub fn contains(&self, x: i32, y: i32) -> bool {
         x > self.x && x < self.x + (self.w as i32) && y > self.y && y < self.y + (self.h as i32)
     }
 }
+
+// Escapes everything but unreserved characters, for putting text into a URL
+pub fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+pub fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+// As minutes and seconds, e.g. "5:12"
+pub fn format_time(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
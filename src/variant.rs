@@ -0,0 +1,1403 @@
+use std::{fmt, str::FromStr};
+
+use rand::Rng;
+
+use crate::{
+    card::{self, Card, Suit, Value},
+    deal,
+    moves::Pile,
+    options::Draw,
+    rules,
+};
+
+const SUIT_SIZE: usize = 13;
+
+/// The kinds of pile a game can be laid out with. Each kind has its own placement rules.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PileKind {
+    // The stock, and the waste it deals onto if it has one, which sits one column to its right
+    Stock,
+    Foundation,
+    Tableau,
+    // Holds any one card
+    Cell,
+    // One card of a pyramid, which can't be played until the cards overlapping it are gone
+    Pyramid,
+    // A face-down pile that only its top card can be played from, and that takes nothing
+    Reserve,
+}
+
+/// Where a pile goes, in columns and rows of card-sized spaces from the top left. Piles that
+/// overlap others can sit part way between them.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PileSpec {
+    pub kind: PileKind,
+    pub column: f32,
+    pub row: f32,
+}
+
+/// Where the stock's cards go when it's clicked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StockRules {
+    // Turned over onto the waste, `draw` at a time. With `recycle`, the waste can be turned back
+    // over into the stock once it runs out.
+    Waste { draw: Draw, recycle: bool },
+    // One card onto each tableau in turn, until the stock runs out. Unless `allow_empty`, this can
+    // only be done while none of them are empty.
+    Tableaus { allow_empty: bool },
+}
+
+/// The cards at the start of a game.
+pub struct Deal {
+    // Cards dealt out before play starts, in the order they're dealt, as (pile index, card, face up)
+    pub cards: Vec<(usize, Card, bool)>,
+    // What's left for the stock, with its top card last
+    pub stock: Vec<Card>,
+}
+
+/// A set of rules for a game of solitaire. Piles are addressed by their index in `piles`.
+pub trait Variant {
+    fn piles(&self) -> Vec<PileSpec>;
+
+    fn deal(&self, seed: u64) -> Deal;
+
+    /// A seed for a new game, picked at random from the ones `deal` accepts.
+    fn random_seed(&self) -> u64 {
+        deal::random_seed()
+    }
+
+    /// Whether `cards` can be placed on a pile of `kind` whose top card is `top`. For the stock,
+    /// that's whether they can be played onto its waste.
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool;
+
+    /// The rank the foundations start from in the deal from `seed`. Aces for most games.
+    fn foundation_base(&self, _seed: u64) -> Value {
+        Value::Ace
+    }
+
+    /// Whether `cards` can be placed on a foundation whose top card is `top`, in a game whose
+    /// foundations start from `base`. Only games that don't always start from Aces need this to
+    /// be any different from `builds_on`.
+    fn builds_on_foundation(&self, cards: &[Card], top: Option<Card>, _base: Value) -> bool {
+        self.builds_on(PileKind::Foundation, cards, top)
+    }
+
+    /// Whether face-up `cards` can be picked up off a pile of `kind` together. By default any can
+    /// be.
+    fn is_movable_group(&self, _kind: PileKind, _cards: &[Card]) -> bool {
+        true
+    }
+
+    /// How many cards on top of a tableau showing face-up `cards` go to a foundation by
+    /// themselves. None for most games, where nothing moves without the player.
+    fn completed_run(&self, _cards: &[Card]) -> Option<usize> {
+        None
+    }
+
+    /// The most cards that can be moved onto a pile of `kind` at once, given how many cells and
+    /// tableaus are empty, not counting the pile being moved onto.
+    fn move_capacity(&self, _kind: PileKind, _empty_cells: usize, _empty_tableaus: usize) -> usize {
+        usize::MAX
+    }
+
+    /// None if the game is played without a stock.
+    fn stock_rules(&self) -> Option<StockRules>;
+
+    /// Whether `card` can be played onto `top` to take both off the board, as pairs are in
+    /// Pyramid. Cards can't be paired up by default.
+    fn pairs_with(&self, _card: Card, _top: Card) -> bool {
+        false
+    }
+
+    /// The piles that overlap the pile at `index`, which has to wait until they're all empty
+    /// before it can be played to or from.
+    fn covered_by(&self, _index: usize) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Whether every card is dealt face up with none kept back in a stock, so a deal can be
+    /// solved exactly before it's played.
+    fn all_cards_visible(&self) -> bool {
+        false
+    }
+
+    /// Whether the game is scored by streaks of cards played onto the waste.
+    fn scores_streaks(&self) -> bool {
+        false
+    }
+
+    /// Whether the game is won, given the cards in each pile, with the stock's including its
+    /// waste. By default that's once every foundation holds a whole suit.
+    fn is_won(&self, piles: &[Vec<Card>]) -> bool {
+        self.piles()
+            .iter()
+            .zip(piles)
+            .filter(|(spec, _)| spec.kind == PileKind::Foundation)
+            .all(|(_, cards)| cards.len() == SUIT_SIZE)
+    }
+
+    /// How the pile at `index` is written in move notation.
+    fn pile_name(&self, index: usize) -> Option<Pile> {
+        let piles = self.piles();
+        let kind = piles.get(index)?.kind;
+        let nth = piles[..index].iter().filter(|p| p.kind == kind).count();
+
+        match kind {
+            PileKind::Stock => Some(Pile::Waste),
+            PileKind::Foundation => Some(Pile::Foundation(nth)),
+            PileKind::Tableau => Some(Pile::Tableau(nth)),
+            PileKind::Cell => Some(Pile::Cell(nth)),
+            PileKind::Pyramid => Some(Pile::Pyramid(nth)),
+            PileKind::Reserve => Some(Pile::Reserve),
+        }
+    }
+
+    /// The index of the pile written as `pile` in move notation.
+    fn pile_index(&self, pile: Pile) -> Option<usize> {
+        let (kind, nth) = match pile {
+            Pile::Waste => (PileKind::Stock, 0),
+            Pile::Foundation(nth) => (PileKind::Foundation, nth),
+            Pile::Tableau(nth) => (PileKind::Tableau, nth),
+            Pile::Cell(nth) => (PileKind::Cell, nth),
+            Pile::Pyramid(nth) => (PileKind::Pyramid, nth),
+            Pile::Reserve => (PileKind::Reserve, 0),
+        };
+
+        self.piles()
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.kind == kind)
+            .nth(nth)
+            .map(|(index, _)| index)
+    }
+}
+
+// Whether every pile of `kind` is empty, for games that are won by clearing them
+fn is_cleared(specs: &[PileSpec], piles: &[Vec<Card>], kind: PileKind) -> bool {
+    specs
+        .iter()
+        .zip(piles)
+        .filter(|(spec, _)| spec.kind == kind)
+        .all(|(_, cards)| cards.is_empty())
+}
+
+/// The games that can be played, for picking one and saving which was played.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VariantKind {
+    #[default]
+    Klondike,
+    FreeCell,
+    Spider(SpiderSuits),
+    Yukon,
+    Russian,
+    Pyramid,
+    // Whether Kings and Aces can be played onto each other
+    TriPeaks {
+        wrap: bool,
+    },
+    Golf,
+    Canfield,
+    Scorpion,
+    FortyThieves,
+    BakersDozen,
+    DoubleKlondike,
+}
+
+impl VariantKind {
+    // Share links store a game as its place in this list, so new games may only be added to the
+    // end. Reordering or removing one would open old links as a different game.
+    pub const ALL: [VariantKind; 16] = [
+        VariantKind::Klondike,
+        VariantKind::FreeCell,
+        VariantKind::Spider(SpiderSuits::One),
+        VariantKind::Spider(SpiderSuits::Two),
+        VariantKind::Spider(SpiderSuits::Four),
+        VariantKind::Yukon,
+        VariantKind::Russian,
+        VariantKind::Pyramid,
+        VariantKind::TriPeaks { wrap: false },
+        VariantKind::TriPeaks { wrap: true },
+        VariantKind::Golf,
+        VariantKind::Canfield,
+        VariantKind::Scorpion,
+        VariantKind::FortyThieves,
+        VariantKind::BakersDozen,
+        VariantKind::DoubleKlondike,
+    ];
+
+    /// The rules for this game. `draw` is ignored by games without a stock.
+    pub fn rules(&self, draw: Draw) -> Box<dyn Variant> {
+        match self {
+            VariantKind::Klondike => Box::new(Klondike::new(draw)),
+            VariantKind::FreeCell => Box::new(FreeCell),
+            VariantKind::Spider(suits) => Box::new(Spider::new(*suits)),
+            VariantKind::Yukon => Box::new(Yukon::new(false)),
+            VariantKind::Russian => Box::new(Yukon::new(true)),
+            VariantKind::Pyramid => Box::new(Pyramid),
+            VariantKind::TriPeaks { wrap } => Box::new(TriPeaks::new(*wrap)),
+            VariantKind::Golf => Box::new(Golf),
+            VariantKind::Canfield => Box::new(Canfield),
+            VariantKind::Scorpion => Box::new(Scorpion),
+            VariantKind::FortyThieves => Box::new(FortyThieves),
+            VariantKind::BakersDozen => Box::new(BakersDozen),
+            VariantKind::DoubleKlondike => Box::new(DoubleKlondike::new(draw)),
+        }
+    }
+}
+
+impl fmt::Display for VariantKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariantKind::Klondike => write!(f, "Klondike"),
+            VariantKind::FreeCell => write!(f, "FreeCell"),
+            VariantKind::Spider(SpiderSuits::One) => write!(f, "Spider (1 suit)"),
+            VariantKind::Spider(suits) => write!(f, "Spider ({} suits)", suits.suits().len()),
+            VariantKind::Yukon => write!(f, "Yukon"),
+            VariantKind::Russian => write!(f, "Russian"),
+            VariantKind::Pyramid => write!(f, "Pyramid"),
+            VariantKind::TriPeaks { wrap: false } => write!(f, "TriPeaks"),
+            VariantKind::TriPeaks { wrap: true } => write!(f, "TriPeaks (K-A wrap)"),
+            VariantKind::Golf => write!(f, "Golf"),
+            VariantKind::Canfield => write!(f, "Canfield"),
+            VariantKind::Scorpion => write!(f, "Scorpion"),
+            VariantKind::FortyThieves => write!(f, "Forty Thieves"),
+            VariantKind::BakersDozen => write!(f, "Baker's Dozen"),
+            VariantKind::DoubleKlondike => write!(f, "Double Klondike"),
+        }
+    }
+}
+
+impl FromStr for VariantKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .find(|kind| kind.to_string() == s)
+            .copied()
+            .ok_or(())
+    }
+}
+
+pub const KLONDIKE_FOUNDATIONS: usize = 4;
+pub const KLONDIKE_TABLEAUS: usize = 7;
+
+/// The classic game, and the one every deal number and record refers to.
+#[derive(Clone, Copy, Debug)]
+pub struct Klondike {
+    draw: Draw,
+}
+
+impl Klondike {
+    pub fn new(draw: Draw) -> Self {
+        Self { draw }
+    }
+}
+
+impl Variant for Klondike {
+    // Foundations come first so they win ties between overlapping drop targets
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..KLONDIKE_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (3 + i) as f32,
+            row: 0.0,
+        });
+        let tableaus = (0..KLONDIKE_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 1.0,
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 0.0,
+            row: 0.0,
+        };
+
+        foundations
+            .chain(tableaus)
+            .chain(std::iter::once(stock))
+            .collect()
+    }
+
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled_deck(seed);
+        let stock = cards.split_off(deal::tableau_card_count(KLONDIKE_TABLEAUS));
+
+        let cards = deal::tableau_deal_order(KLONDIKE_TABLEAUS)
+            .zip(cards)
+            .map(|((column, face_up), card)| (KLONDIKE_FOUNDATIONS + column, card, face_up))
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards.first()) {
+            (PileKind::Foundation, _) => rules::builds_on_foundation(cards, top),
+            (PileKind::Tableau, Some(card)) => rules::builds_on_tableau(*card, top),
+            _ => false,
+        }
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Waste {
+            draw: self.draw,
+            recycle: true,
+        })
+    }
+}
+
+const FREECELL_CELLS: usize = 4;
+const FREECELL_FOUNDATIONS: usize = 4;
+const FREECELL_TABLEAUS: usize = 8;
+// The deals the original Windows game had
+const FREECELL_DEAL_COUNT: u64 = 32000;
+
+/// Every card is dealt face up, and four cells give room to move them around. Seeds are the
+/// numbered deals from the Windows game.
+#[derive(Clone, Copy, Debug)]
+pub struct FreeCell;
+
+impl Variant for FreeCell {
+    // Tableaus come before cells so an empty tableau is preferred when a card is sent somewhere
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..FREECELL_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (FREECELL_CELLS + i) as f32,
+            row: 0.0,
+        });
+        let tableaus = (0..FREECELL_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 1.0,
+        });
+        let cells = (0..FREECELL_CELLS).map(|i| PileSpec {
+            kind: PileKind::Cell,
+            column: i as f32,
+            row: 0.0,
+        });
+
+        foundations.chain(tableaus).chain(cells).collect()
+    }
+
+    // Dealt in rows from left to right, as the Windows game did
+    fn deal(&self, seed: u64) -> Deal {
+        let cards = deal::microsoft_deal(seed)
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| (FREECELL_FOUNDATIONS + i % FREECELL_TABLEAUS, card, true))
+            .collect();
+
+        Deal {
+            cards,
+            stock: Vec::new(),
+        }
+    }
+
+    fn random_seed(&self) -> u64 {
+        rand::thread_rng().gen_range(1..=FREECELL_DEAL_COUNT)
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards.first(), top) {
+            (PileKind::Foundation, _, _) => rules::builds_on_foundation(cards, top),
+            // Any card can go on an empty tableau
+            (PileKind::Tableau, Some(_), None) => true,
+            (PileKind::Tableau, Some(card), top) => rules::builds_on_tableau(*card, top),
+            (PileKind::Cell, Some(_), None) => cards.len() == 1,
+            _ => false,
+        }
+    }
+
+    fn is_movable_group(&self, kind: PileKind, cards: &[Card]) -> bool {
+        match kind {
+            PileKind::Tableau => rules::is_tableau_run(cards),
+            PileKind::Foundation => false,
+            _ => true,
+        }
+    }
+
+    // Only one card is really moved at a time, so a run can only be moved if there's room to
+    // shuffle it across one card at a time. Each empty tableau doubles how many cards fit.
+    fn move_capacity(&self, kind: PileKind, empty_cells: usize, empty_tableaus: usize) -> usize {
+        match kind {
+            PileKind::Tableau => (empty_cells + 1) << empty_tableaus,
+            _ => 1,
+        }
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        None
+    }
+
+    fn all_cards_visible(&self) -> bool {
+        true
+    }
+}
+
+/// How many suits a game of Spider is played with. The fewer there are, the easier it is to
+/// build runs that can be moved.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SpiderSuits {
+    One,
+    Two,
+    Four,
+}
+
+impl SpiderSuits {
+    fn suits(&self) -> &'static [Suit] {
+        match self {
+            SpiderSuits::One => &[Suit::Spades],
+            SpiderSuits::Two => &[Suit::Spades, Suit::Hearts],
+            SpiderSuits::Four => &[Suit::Spades, Suit::Hearts, Suit::Clubs, Suit::Diamonds],
+        }
+    }
+}
+
+const SPIDER_DECKS: usize = 2;
+const SPIDER_FOUNDATIONS: usize = 8;
+const SPIDER_TABLEAUS: usize = 10;
+// The rest are left in the stock, enough for five more rows
+const SPIDER_DEALT_CARDS: usize = 54;
+
+/// Two decks in ten columns. Runs can be built down in any suit, but only move together when
+/// they're all one suit, and a whole suit from King to Ace leaves the board once it's built.
+#[derive(Clone, Copy, Debug)]
+pub struct Spider {
+    suits: SpiderSuits,
+}
+
+impl Spider {
+    pub fn new(suits: SpiderSuits) -> Self {
+        Self { suits }
+    }
+}
+
+impl Variant for Spider {
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..SPIDER_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (SPIDER_TABLEAUS - SPIDER_FOUNDATIONS + i) as f32,
+            row: 0.0,
+        });
+        let tableaus = (0..SPIDER_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 1.0,
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 0.0,
+            row: 0.0,
+        };
+
+        foundations
+            .chain(tableaus)
+            .chain(std::iter::once(stock))
+            .collect()
+    }
+
+    // Dealt in rows, so the first four columns get an extra card, and the last row is face up
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled(card::decks(SPIDER_DECKS, self.suits.suits()), seed);
+        let stock = cards.split_off(SPIDER_DEALT_CARDS);
+
+        let cards = cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| {
+                let face_up = i + SPIDER_TABLEAUS >= SPIDER_DEALT_CARDS;
+                (SPIDER_FOUNDATIONS + i % SPIDER_TABLEAUS, card, face_up)
+            })
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards.first(), top) {
+            (PileKind::Foundation, _, None) => self.completed_run(cards) == Some(cards.len()),
+            (PileKind::Tableau, Some(_), None) => true,
+            (PileKind::Tableau, Some(Card(value, _)), Some(Card(top_value, _))) => {
+                top_value.prev_value() == Some(*value)
+            }
+            _ => false,
+        }
+    }
+
+    // Completed suits stay on the foundations
+    fn is_movable_group(&self, kind: PileKind, cards: &[Card]) -> bool {
+        match kind {
+            PileKind::Tableau => rules::is_suit_run(cards),
+            PileKind::Foundation => false,
+            _ => true,
+        }
+    }
+
+    fn completed_run(&self, cards: &[Card]) -> Option<usize> {
+        rules::ends_in_full_suit(cards).then_some(SUIT_SIZE)
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Tableaus { allow_empty: false })
+    }
+}
+
+const YUKON_FOUNDATIONS: usize = 4;
+const YUKON_TABLEAUS: usize = 7;
+// Cards dealt face up onto every column but the first once the Klondike deal is done
+const YUKON_EXTRA_ROWS: usize = 4;
+
+/// Dealt like Klondike, with the rest of the deck added face up instead of kept in a stock. Any
+/// face-up card can be moved along with whatever is on top of it, in sequence or not. Russian
+/// Solitaire is the same, but built down in suit.
+#[derive(Clone, Copy, Debug)]
+pub struct Yukon {
+    in_suit: bool,
+}
+
+impl Yukon {
+    pub fn new(in_suit: bool) -> Self {
+        Self { in_suit }
+    }
+}
+
+impl Variant for Yukon {
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..YUKON_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (3 + i) as f32,
+            row: 0.0,
+        });
+        let tableaus = (0..YUKON_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 1.0,
+        });
+
+        foundations.chain(tableaus).collect()
+    }
+
+    fn deal(&self, seed: u64) -> Deal {
+        let extra_rows =
+            (0..YUKON_EXTRA_ROWS).flat_map(|_| (1..YUKON_TABLEAUS).map(|column| (column, true)));
+
+        let cards = deal::tableau_deal_order(YUKON_TABLEAUS)
+            .chain(extra_rows)
+            .zip(deal::shuffled_deck(seed))
+            .map(|((column, face_up), card)| (YUKON_FOUNDATIONS + column, card, face_up))
+            .collect();
+
+        Deal {
+            cards,
+            stock: Vec::new(),
+        }
+    }
+
+    // Only the card at the bottom of the group being moved has to follow on from the top card
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards.first()) {
+            (PileKind::Foundation, _) => rules::builds_on_foundation(cards, top),
+            (PileKind::Tableau, Some(card)) if self.in_suit => {
+                rules::builds_on_tableau_in_suit(*card, top)
+            }
+            (PileKind::Tableau, Some(card)) => rules::builds_on_tableau(*card, top),
+            _ => false,
+        }
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        None
+    }
+}
+
+const PYRAMID_ROWS: usize = 7;
+const PYRAMID_CARDS: usize = PYRAMID_ROWS * (PYRAMID_ROWS + 1) / 2;
+// Where the top of the pyramid sits, with the stock and waste to its left
+const PYRAMID_APEX_COLUMN: f32 = 4.0;
+// How far down each row of the pyramid is from the one above, in rows of cards
+const PYRAMID_ROW_STEP: f32 = 0.5;
+
+/// 28 cards dealt face up in a pyramid, each row overlapping the one above. Cards are taken off
+/// in pairs that add up to 13, or on their own if they're Kings, once nothing overlaps them. Every
+/// card removed goes onto a single foundation, and the game is won once the pyramid is cleared.
+#[derive(Clone, Copy, Debug)]
+pub struct Pyramid;
+
+impl Pyramid {
+    // The pile index of the card in `row` of the pyramid, `position` cards from the left
+    fn pile_index(row: usize, position: usize) -> usize {
+        1 + row * (row + 1) / 2 + position
+    }
+}
+
+impl Variant for Pyramid {
+    fn piles(&self) -> Vec<PileSpec> {
+        // One column along from the end of the bottom row
+        let foundation = PileSpec {
+            kind: PileKind::Foundation,
+            column: PYRAMID_APEX_COLUMN + (PYRAMID_ROWS - 1) as f32 / 2.0 + 1.0,
+            row: 0.0,
+        };
+        let pyramid = (0..PYRAMID_ROWS).flat_map(|row| {
+            (0..=row).map(move |position| PileSpec {
+                kind: PileKind::Pyramid,
+                column: PYRAMID_APEX_COLUMN - row as f32 / 2.0 + position as f32,
+                row: row as f32 * PYRAMID_ROW_STEP,
+            })
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 0.0,
+            row: 0.0,
+        };
+
+        std::iter::once(foundation)
+            .chain(pyramid)
+            .chain(std::iter::once(stock))
+            .collect()
+    }
+
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled_deck(seed);
+        let stock = cards.split_off(PYRAMID_CARDS);
+
+        let cards = cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| (Self::pile_index(0, 0) + i, card, true))
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], _top: Option<Card>) -> bool {
+        kind == PileKind::Foundation && rules::is_pyramid_pair(cards)
+    }
+
+    // Cards only ever go onto the foundation to leave the board
+    fn is_movable_group(&self, kind: PileKind, _cards: &[Card]) -> bool {
+        kind != PileKind::Foundation
+    }
+
+    fn pairs_with(&self, card: Card, top: Card) -> bool {
+        rules::is_pyramid_pair(&[card, top])
+    }
+
+    fn covered_by(&self, index: usize) -> Vec<usize> {
+        let first = Self::pile_index(0, 0);
+        if !(first..first + PYRAMID_CARDS).contains(&index) {
+            return Vec::new();
+        }
+
+        let offset = index - first;
+        let row = (0..PYRAMID_ROWS)
+            .find(|row| offset < (row + 1) * (row + 2) / 2)
+            .expect("index should be in the pyramid");
+        let position = offset - row * (row + 1) / 2;
+
+        if row + 1 < PYRAMID_ROWS {
+            vec![
+                Self::pile_index(row + 1, position),
+                Self::pile_index(row + 1, position + 1),
+            ]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Waste {
+            draw: Draw::One,
+            recycle: true,
+        })
+    }
+
+    fn is_won(&self, piles: &[Vec<Card>]) -> bool {
+        is_cleared(&self.piles(), piles, PileKind::Pyramid)
+    }
+}
+
+// The rows of each peak, from the top
+const TRIPEAKS_PEAK_ROWS: [usize; 3] = [1, 2, 3];
+const TRIPEAKS_PEAKS: usize = 3;
+const TRIPEAKS_PEAK_WIDTH: usize = 3;
+// The row along the bottom that joins the peaks
+const TRIPEAKS_BASE: usize = 10;
+const TRIPEAKS_CARDS: usize = 28;
+const TRIPEAKS_ROW_STEP: f32 = 0.5;
+
+/// Three overlapping peaks of cards, face down until they're uncovered. Uncovered cards are
+/// played onto the waste if they're one higher or one lower than its top card, and the stock
+/// turns over one card at a time when nothing can be played. The game is won once the peaks are
+/// cleared.
+#[derive(Clone, Copy, Debug)]
+pub struct TriPeaks {
+    wrap: bool,
+}
+
+impl TriPeaks {
+    pub fn new(wrap: bool) -> Self {
+        Self { wrap }
+    }
+
+    // The index of the first card in each row, from the top, and one past the last card
+    fn row_starts() -> [usize; 5] {
+        let peak_row = |row: usize| TRIPEAKS_PEAK_ROWS[row] * TRIPEAKS_PEAKS;
+        [
+            0,
+            peak_row(0),
+            peak_row(0) + peak_row(1),
+            peak_row(0) + peak_row(1) + peak_row(2),
+            TRIPEAKS_CARDS,
+        ]
+    }
+}
+
+impl Variant for TriPeaks {
+    // The peaks are listed a row at a time from the top, then the stock
+    fn piles(&self) -> Vec<PileSpec> {
+        let peak = |row: usize, index: usize| {
+            let width = TRIPEAKS_PEAK_ROWS[row];
+            let (nth_peak, position) = (index / width, index % width);
+            // Each peak is as wide as its bottom row, and each row down starts half a column
+            // further left
+            let column = (nth_peak * TRIPEAKS_PEAK_WIDTH) as f32 + 1.5 - row as f32 / 2.0;
+            PileSpec {
+                kind: PileKind::Pyramid,
+                column: column + position as f32,
+                row: row as f32 * TRIPEAKS_ROW_STEP,
+            }
+        };
+        let peaks = (0..TRIPEAKS_PEAK_ROWS.len()).flat_map(move |row| {
+            (0..TRIPEAKS_PEAK_ROWS[row] * TRIPEAKS_PEAKS).map(move |index| peak(row, index))
+        });
+        let base_row = TRIPEAKS_PEAK_ROWS.len() as f32 * TRIPEAKS_ROW_STEP;
+        let base = (0..TRIPEAKS_BASE).map(move |column| PileSpec {
+            kind: PileKind::Pyramid,
+            column: column as f32,
+            row: base_row,
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 3.0,
+            row: base_row + 1.0,
+        };
+
+        peaks.chain(base).chain(std::iter::once(stock)).collect()
+    }
+
+    // Only the bottom row starts face up, and one card is turned over onto the waste
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled_deck(seed);
+        let mut stock = cards.split_off(TRIPEAKS_CARDS);
+        let waste = stock
+            .pop()
+            .expect("there should be cards left for the stock");
+
+        let base = Self::row_starts()[TRIPEAKS_PEAK_ROWS.len()];
+        let cards = cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| (i, card, i >= base))
+            .chain(std::iter::once((TRIPEAKS_CARDS, waste, true)))
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards) {
+            (PileKind::Stock, [card]) => rules::plays_onto_waste(*card, top, self.wrap),
+            _ => false,
+        }
+    }
+
+    // Cards played onto the waste stay there
+    fn is_movable_group(&self, kind: PileKind, _cards: &[Card]) -> bool {
+        kind != PileKind::Stock
+    }
+
+    // Each card is covered by the two cards below it that it overlaps. Between the peaks, those
+    // are in the next row along from where the card is in its own row, except that the rows
+    // above the base are split into separate peaks.
+    fn covered_by(&self, index: usize) -> Vec<usize> {
+        let starts = Self::row_starts();
+        let row = match (0..TRIPEAKS_PEAK_ROWS.len()).find(|row| index < starts[row + 1]) {
+            Some(row) => row,
+            None => return Vec::new(),
+        };
+        let position = index - starts[row];
+        let width = TRIPEAKS_PEAK_ROWS[row];
+        // How far along the row below the left-hand card is, counting across the peaks
+        let below = if row + 1 < TRIPEAKS_PEAK_ROWS.len() {
+            (position / width) * TRIPEAKS_PEAK_ROWS[row + 1] + position % width
+        } else {
+            position
+        };
+
+        vec![starts[row + 1] + below, starts[row + 1] + below + 1]
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Waste {
+            draw: Draw::One,
+            recycle: false,
+        })
+    }
+
+    fn scores_streaks(&self) -> bool {
+        true
+    }
+
+    fn is_won(&self, piles: &[Vec<Card>]) -> bool {
+        is_cleared(&self.piles(), piles, PileKind::Pyramid)
+    }
+}
+
+const GOLF_TABLEAUS: usize = 7;
+const GOLF_ROWS: usize = 5;
+
+/// Seven columns of five face-up cards. The top card of a column can be played onto the waste if
+/// it's one higher or one lower than the waste's top card, with no wrapping between Kings and
+/// Aces, and the game is won once every column is cleared.
+#[derive(Clone, Copy, Debug)]
+pub struct Golf;
+
+impl Variant for Golf {
+    fn piles(&self) -> Vec<PileSpec> {
+        let tableaus = (0..GOLF_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 0.0,
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 2.0,
+            row: 2.0,
+        };
+
+        tableaus.chain(std::iter::once(stock)).collect()
+    }
+
+    // Dealt in rows, then one card is turned over onto the waste
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled_deck(seed);
+        let mut stock = cards.split_off(GOLF_TABLEAUS * GOLF_ROWS);
+        let waste = stock
+            .pop()
+            .expect("there should be cards left for the stock");
+
+        let cards = cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| (i % GOLF_TABLEAUS, card, true))
+            .chain(std::iter::once((GOLF_TABLEAUS, waste, true)))
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards) {
+            (PileKind::Stock, [card]) => rules::plays_onto_waste(*card, top, false),
+            _ => false,
+        }
+    }
+
+    // Only the top card of a column can be played, and cards played onto the waste stay there
+    fn is_movable_group(&self, kind: PileKind, cards: &[Card]) -> bool {
+        kind == PileKind::Tableau && cards.len() == 1
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Waste {
+            draw: Draw::One,
+            recycle: false,
+        })
+    }
+
+    fn scores_streaks(&self) -> bool {
+        true
+    }
+
+    fn is_won(&self, piles: &[Vec<Card>]) -> bool {
+        is_cleared(&self.piles(), piles, PileKind::Tableau)
+    }
+}
+
+const CANFIELD_RESERVE: usize = 13;
+const CANFIELD_FOUNDATIONS: usize = 4;
+const CANFIELD_TABLEAUS: usize = 4;
+
+/// Thirteen cards in a reserve to play out, four tableaus, and foundations that start from
+/// whatever rank is dealt onto the first of them and wrap round from King to Ace. Tableaus build
+/// down in alternating colours, also wrapping, and the stock turns over three at a time with no
+/// limit on passes.
+#[derive(Clone, Copy, Debug)]
+pub struct Canfield;
+
+impl Variant for Canfield {
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..CANFIELD_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (3 + i) as f32,
+            row: 0.0,
+        });
+        let tableaus = (0..CANFIELD_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: (3 + i) as f32,
+            row: 1.0,
+        });
+        let reserve = PileSpec {
+            kind: PileKind::Reserve,
+            column: 0.0,
+            row: 1.0,
+        };
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 0.0,
+            row: 0.0,
+        };
+
+        foundations
+            .chain(tableaus)
+            .chain([reserve, stock])
+            .collect()
+    }
+
+    // The reserve first, with only its top card face up, then one card to start the first
+    // foundation and one onto each tableau
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled_deck(seed);
+        let stock = cards.split_off(CANFIELD_RESERVE + 1 + CANFIELD_TABLEAUS);
+
+        let reserve = CANFIELD_FOUNDATIONS + CANFIELD_TABLEAUS;
+        let piles = (0..CANFIELD_RESERVE)
+            .map(|i| (reserve, i == CANFIELD_RESERVE - 1))
+            .chain(std::iter::once((0, true)))
+            .chain((0..CANFIELD_TABLEAUS).map(|i| (CANFIELD_FOUNDATIONS + i, true)));
+        let cards = piles
+            .zip(cards)
+            .map(|((pile, face_up), card)| (pile, card, face_up))
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn foundation_base(&self, seed: u64) -> Value {
+        let Card(value, _) = deal::shuffled_deck(seed)[CANFIELD_RESERVE];
+        value
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards.first()) {
+            // Foundations depend on the deal, so they're left to `builds_on_foundation`
+            (PileKind::Tableau, Some(card)) => rules::builds_on_tableau_wrapping(*card, top),
+            _ => false,
+        }
+    }
+
+    fn builds_on_foundation(&self, cards: &[Card], top: Option<Card>, base: Value) -> bool {
+        rules::builds_on_foundation_from(cards, top, base)
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Waste {
+            draw: Draw::Three,
+            recycle: true,
+        })
+    }
+}
+
+const SCORPION_FOUNDATIONS: usize = 4;
+const SCORPION_TABLEAUS: usize = 7;
+const SCORPION_ROWS: usize = 7;
+// The first four columns have their first three cards dealt face down
+const SCORPION_FACE_DOWN_COLUMNS: usize = 4;
+const SCORPION_FACE_DOWN_ROWS: usize = 3;
+
+/// Seven columns of seven, with the last three cards kept back and dealt onto the first three
+/// columns when the stock is clicked. Columns build down in suit, any face-up card can be moved
+/// along with whatever is on top of it like in Yukon, and a whole suit from King to Ace leaves the
+/// board once it's built like in Spider.
+#[derive(Clone, Copy, Debug)]
+pub struct Scorpion;
+
+impl Variant for Scorpion {
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..SCORPION_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (SCORPION_TABLEAUS - SCORPION_FOUNDATIONS + i) as f32,
+            row: 0.0,
+        });
+        let tableaus = (0..SCORPION_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 1.0,
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 0.0,
+            row: 0.0,
+        };
+
+        foundations
+            .chain(tableaus)
+            .chain(std::iter::once(stock))
+            .collect()
+    }
+
+    // Dealt in rows
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled_deck(seed);
+        let stock = cards.split_off(SCORPION_TABLEAUS * SCORPION_ROWS);
+
+        let cards = cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| {
+                let (row, column) = (i / SCORPION_TABLEAUS, i % SCORPION_TABLEAUS);
+                let face_up =
+                    row >= SCORPION_FACE_DOWN_ROWS || column >= SCORPION_FACE_DOWN_COLUMNS;
+                (SCORPION_FOUNDATIONS + column, card, face_up)
+            })
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards.first(), top) {
+            (PileKind::Foundation, _, None) => self.completed_run(cards) == Some(cards.len()),
+            (PileKind::Tableau, Some(card), _) => rules::builds_on_tableau_in_suit(*card, top),
+            _ => false,
+        }
+    }
+
+    // Completed suits stay on the foundations
+    fn is_movable_group(&self, kind: PileKind, _cards: &[Card]) -> bool {
+        kind != PileKind::Foundation
+    }
+
+    fn completed_run(&self, cards: &[Card]) -> Option<usize> {
+        rules::ends_in_full_suit(cards).then_some(SUIT_SIZE)
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Tableaus { allow_empty: true })
+    }
+}
+
+const FORTY_THIEVES_DECKS: usize = 2;
+const FORTY_THIEVES_FOUNDATIONS: usize = 8;
+const FORTY_THIEVES_TABLEAUS: usize = 10;
+const FORTY_THIEVES_ROWS: usize = 4;
+// Eight foundations don't fit in a row beside the stock and its waste, so they're stacked in two
+const FORTY_THIEVES_FOUNDATIONS_PER_ROW: usize = 4;
+
+/// Two decks, with ten columns of four face-up cards and the rest in the stock. Columns build
+/// down in suit, only one card can be moved at a time, and any card can go into an empty column.
+/// The stock is turned over one card at a time, and only once.
+#[derive(Clone, Copy, Debug)]
+pub struct FortyThieves;
+
+impl Variant for FortyThieves {
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..FORTY_THIEVES_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (FORTY_THIEVES_TABLEAUS - FORTY_THIEVES_FOUNDATIONS_PER_ROW
+                + i % FORTY_THIEVES_FOUNDATIONS_PER_ROW) as f32,
+            row: (i / FORTY_THIEVES_FOUNDATIONS_PER_ROW) as f32,
+        });
+        let tableaus = (0..FORTY_THIEVES_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 2.0,
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 0.0,
+            row: 0.0,
+        };
+
+        foundations
+            .chain(tableaus)
+            .chain(std::iter::once(stock))
+            .collect()
+    }
+
+    // Dealt in rows
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled(card::DECK.repeat(FORTY_THIEVES_DECKS), seed);
+        let stock = cards.split_off(FORTY_THIEVES_TABLEAUS * FORTY_THIEVES_ROWS);
+
+        let cards = cards
+            .into_iter()
+            .enumerate()
+            .map(|(i, card)| {
+                let column = i % FORTY_THIEVES_TABLEAUS;
+                (FORTY_THIEVES_FOUNDATIONS + column, card, true)
+            })
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards, top) {
+            (PileKind::Foundation, _, _) => rules::builds_on_foundation(cards, top),
+            (PileKind::Tableau, [_], None) => true,
+            (PileKind::Tableau, [card], Some(_)) => rules::builds_on_tableau_in_suit(*card, top),
+            _ => false,
+        }
+    }
+
+    fn is_movable_group(&self, kind: PileKind, cards: &[Card]) -> bool {
+        kind != PileKind::Foundation && cards.len() == 1
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Waste {
+            draw: Draw::One,
+            recycle: false,
+        })
+    }
+}
+
+const BAKERS_DOZEN_FOUNDATIONS: usize = 4;
+const BAKERS_DOZEN_TABLEAUS: usize = 13;
+
+/// The whole deck dealt face up into thirteen columns of four, with any Kings moved to the bottom
+/// of their column. Columns build down regardless of suit, one card at a time, and nothing can
+/// be put into an empty column.
+#[derive(Clone, Copy, Debug)]
+pub struct BakersDozen;
+
+impl Variant for BakersDozen {
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..BAKERS_DOZEN_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (BAKERS_DOZEN_TABLEAUS - BAKERS_DOZEN_FOUNDATIONS + i) as f32,
+            row: 0.0,
+        });
+        let tableaus = (0..BAKERS_DOZEN_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 1.0,
+        });
+
+        foundations.chain(tableaus).collect()
+    }
+
+    // Dealt in rows, after sorting each column's Kings to the bottom so none of them start out
+    // covering a card
+    fn deal(&self, seed: u64) -> Deal {
+        let mut columns = vec![Vec::new(); BAKERS_DOZEN_TABLEAUS];
+        for (i, card) in deal::shuffled_deck(seed).into_iter().enumerate() {
+            columns[i % BAKERS_DOZEN_TABLEAUS].push(card);
+        }
+        for column in &mut columns {
+            column.sort_by_key(|Card(value, _)| *value != Value::King);
+        }
+
+        let rows = columns[0].len();
+        let cards = (0..rows)
+            .flat_map(|row| (0..BAKERS_DOZEN_TABLEAUS).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                (
+                    BAKERS_DOZEN_FOUNDATIONS + column,
+                    columns[column][row],
+                    true,
+                )
+            })
+            .collect();
+
+        Deal {
+            cards,
+            stock: Vec::new(),
+        }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards, top) {
+            (PileKind::Foundation, _, _) => rules::builds_on_foundation(cards, top),
+            (PileKind::Tableau, [Card(value, _)], Some(Card(top_value, _))) => {
+                top_value.prev_value() == Some(*value)
+            }
+            _ => false,
+        }
+    }
+
+    // Cards on the foundations stay there
+    fn is_movable_group(&self, kind: PileKind, cards: &[Card]) -> bool {
+        kind != PileKind::Foundation && cards.len() == 1
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        None
+    }
+
+    fn all_cards_visible(&self) -> bool {
+        true
+    }
+}
+
+const DOUBLE_KLONDIKE_DECKS: usize = 2;
+const DOUBLE_KLONDIKE_FOUNDATIONS: usize = 8;
+const DOUBLE_KLONDIKE_TABLEAUS: usize = 9;
+// Eight foundations don't fit in a row beside the stock and its waste, so they're stacked in two
+const DOUBLE_KLONDIKE_FOUNDATIONS_PER_ROW: usize = 4;
+
+/// Klondike with two decks, dealt into nine columns, and built up onto eight foundations. Any
+/// Ace can start any empty foundation, and the stock is drawn with the usual draw rule.
+#[derive(Clone, Copy, Debug)]
+pub struct DoubleKlondike {
+    draw: Draw,
+}
+
+impl DoubleKlondike {
+    pub fn new(draw: Draw) -> Self {
+        Self { draw }
+    }
+}
+
+impl Variant for DoubleKlondike {
+    // The foundations start half a column further along than usual, to leave room for the waste
+    // to fan out
+    fn piles(&self) -> Vec<PileSpec> {
+        let foundations = (0..DOUBLE_KLONDIKE_FOUNDATIONS).map(|i| PileSpec {
+            kind: PileKind::Foundation,
+            column: (DOUBLE_KLONDIKE_TABLEAUS - DOUBLE_KLONDIKE_FOUNDATIONS_PER_ROW
+                + i % DOUBLE_KLONDIKE_FOUNDATIONS_PER_ROW) as f32,
+            row: (i / DOUBLE_KLONDIKE_FOUNDATIONS_PER_ROW) as f32,
+        });
+        let tableaus = (0..DOUBLE_KLONDIKE_TABLEAUS).map(|i| PileSpec {
+            kind: PileKind::Tableau,
+            column: i as f32,
+            row: 2.0,
+        });
+        let stock = PileSpec {
+            kind: PileKind::Stock,
+            column: 0.0,
+            row: 0.0,
+        };
+
+        foundations
+            .chain(tableaus)
+            .chain(std::iter::once(stock))
+            .collect()
+    }
+
+    fn deal(&self, seed: u64) -> Deal {
+        let mut cards = deal::shuffled(card::DECK.repeat(DOUBLE_KLONDIKE_DECKS), seed);
+        let stock = cards.split_off(deal::tableau_card_count(DOUBLE_KLONDIKE_TABLEAUS));
+
+        let cards = deal::tableau_deal_order(DOUBLE_KLONDIKE_TABLEAUS)
+            .zip(cards)
+            .map(|((column, face_up), card)| (DOUBLE_KLONDIKE_FOUNDATIONS + column, card, face_up))
+            .collect();
+
+        Deal { cards, stock }
+    }
+
+    fn builds_on(&self, kind: PileKind, cards: &[Card], top: Option<Card>) -> bool {
+        match (kind, cards.first()) {
+            (PileKind::Foundation, _) => rules::builds_on_foundation(cards, top),
+            (PileKind::Tableau, Some(card)) => rules::builds_on_tableau(*card, top),
+            _ => false,
+        }
+    }
+
+    fn stock_rules(&self) -> Option<StockRules> {
+        Some(StockRules::Waste {
+            draw: self.draw,
+            recycle: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freecell_move_capacity() {
+        for cells in 0..=FREECELL_CELLS {
+            for tableaus in 0..FREECELL_TABLEAUS {
+                assert_eq!(
+                    FreeCell.move_capacity(PileKind::Tableau, cells, tableaus),
+                    (cells + 1) << tableaus
+                );
+            }
+        }
+        // Cells and foundations only ever take one card
+        assert_eq!(FreeCell.move_capacity(PileKind::Cell, 4, 4), 1);
+        assert_eq!(FreeCell.move_capacity(PileKind::Foundation, 4, 4), 1);
+    }
+
+    #[test]
+    fn piles_fit_above_the_tableaus() {
+        for kind in VariantKind::ALL.iter() {
+            let piles = kind.rules(Draw::Three).piles();
+            let tableaus = piles.iter().filter(|spec| spec.kind == PileKind::Tableau);
+            let last_tableau = tableaus.map(|spec| spec.column).fold(None, |last, column| {
+                Some(f32::max(column, last.unwrap_or(column)))
+            });
+
+            // Games without tableaus are laid out some other way
+            if let Some(last_tableau) = last_tableau {
+                assert!(
+                    piles.iter().all(|spec| spec.column <= last_tableau),
+                    "{} has a pile past its last tableau",
+                    kind
+                );
+            }
+
+            // The waste is a column right of the stock, with up to two cards fanned 35px further
+            // right, so it ends 125 + 70px in at 145px a column
+            let stock = piles.iter().find(|spec| spec.kind == PileKind::Stock);
+            if let (Some(stock), Some(StockRules::Waste { .. })) =
+                (stock, kind.rules(Draw::Three).stock_rules())
+            {
+                let waste_end = stock.column + 1.0 + (125.0 + 70.0) / 145.0;
+                assert!(
+                    piles.iter().all(|spec| spec.row != stock.row
+                        || spec.column <= stock.column
+                        || spec.column >= waste_end),
+                    "{} has a pile under its waste",
+                    kind
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn freecell_foundations_keep_their_cards() {
+        let ace = [Card(Value::Ace, Suit::Hearts)];
+        assert!(!FreeCell.is_movable_group(PileKind::Foundation, &ace));
+        assert!(FreeCell.is_movable_group(PileKind::Cell, &ace));
+        assert!(FreeCell.is_movable_group(PileKind::Tableau, &ace));
+    }
+
+    #[test]
+    fn freecell_deals_in_rows() {
+        let deal = FreeCell.deal(1);
+        let first_column = deal
+            .cards
+            .iter()
+            .filter(|(pile, _, _)| FreeCell.pile_name(*pile) == Some(Pile::Tableau(0)))
+            .map(|(_, card, _)| format!("{:?}", card))
+            .collect::<Vec<_>>();
+        let expected = deal::microsoft_deal(1)
+            .into_iter()
+            .step_by(FREECELL_TABLEAUS)
+            .map(|card| format!("{:?}", card))
+            .collect::<Vec<_>>();
+
+        assert_eq!(first_column.len(), 7);
+        assert_eq!(first_column, expected);
+        assert!(deal.cards.iter().all(|(_, _, face_up)| *face_up));
+        assert!(deal.stock.is_empty());
+    }
+}
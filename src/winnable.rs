@@ -0,0 +1,368 @@
+use crate::{
+    deal,
+    difficulty::Difficulty,
+    options::Draw,
+    solver::{SolveResult, Solver},
+};
+
+// Most deals that can be won are solved within a few thousand positions, so it's quicker to move
+// on to another seed than to keep searching a hard one.
+const NODE_LIMIT: usize = 20_000;
+
+// How many seeds a sequence tries before settling for one from the table, which keeps it from
+// running into the seeds of the next sequence
+const SEQUENCE_LENGTH: u64 = 1000;
+
+// Seeds the solver has already shown can be won, with their ratings, used when searching takes
+// too long
+const WINNABLE_DRAW_ONE_SEEDS: &[(u64, Difficulty)] = &[
+    (1482817706323250795, Difficulty::Medium),
+    (11004592982271133285, Difficulty::Medium),
+    (4045824405258374466, Difficulty::Medium),
+    (5233167733899381733, Difficulty::Medium),
+    (13108444932406911064, Difficulty::Medium),
+    (8505575526562236951, Difficulty::Medium),
+    (2815945222763399310, Difficulty::Easy),
+    (16136862674430550035, Difficulty::Easy),
+    (10647629229712230883, Difficulty::Easy),
+    (1834704363358752291, Difficulty::Easy),
+    (8364855488451902434, Difficulty::Easy),
+    (5169307577230649117, Difficulty::Easy),
+    (18281320560898357213, Difficulty::Hard),
+    (16829977863810789899, Difficulty::Hard),
+    (2457916351889425373, Difficulty::Easy),
+    (6339852842806841387, Difficulty::Hard),
+    (8860012633352321510, Difficulty::Easy),
+    (7329526656719895265, Difficulty::Medium),
+    (252675064631575638, Difficulty::Easy),
+    (902408894705456202, Difficulty::Easy),
+    (3415036941779801038, Difficulty::Medium),
+    (9694224649577353294, Difficulty::Medium),
+    (4946171078448925410, Difficulty::Easy),
+    (18281032819509588704, Difficulty::Easy),
+    (12820400435137843114, Difficulty::Medium),
+    (1996776253156376036, Difficulty::Hard),
+    (13479914210769576290, Difficulty::Medium),
+    (12998508074501749244, Difficulty::Easy),
+    (4121387718431265268, Difficulty::Medium),
+    (17582267639568105262, Difficulty::Medium),
+    (11667984563878403184, Difficulty::Easy),
+    (16594832172295386653, Difficulty::Easy),
+    (6996984945834275366, Difficulty::Easy),
+    (8796093458856242187, Difficulty::Hard),
+    (17011384122858770196, Difficulty::Hard),
+    (5196972465386319264, Difficulty::Hard),
+    (4184256848438442426, Difficulty::Easy),
+    (17274135622726802305, Difficulty::Easy),
+    (6362516215513625292, Difficulty::Easy),
+    (18376587406419375687, Difficulty::Hard),
+    (16406235192960711032, Difficulty::Easy),
+    (13510772760070358376, Difficulty::Easy),
+    (11989010672737025243, Difficulty::Easy),
+    (10489039994977515612, Difficulty::Medium),
+    (11332454291334402289, Difficulty::Easy),
+    (11960412381014322328, Difficulty::Easy),
+    (11189423354344598511, Difficulty::Medium),
+    (1201735499287524397, Difficulty::Easy),
+    (7757070574527979098, Difficulty::Hard),
+    (6690381949989353758, Difficulty::Medium),
+    (10874149728859677166, Difficulty::Easy),
+    (5078154303516877726, Difficulty::Medium),
+    (16419605375061295437, Difficulty::Easy),
+    (12066207678277490928, Difficulty::Easy),
+    (3992870565721734456, Difficulty::Hard),
+    (11449823238667019188, Difficulty::Medium),
+    (4674176065730021838, Difficulty::Medium),
+    (18142308545952361121, Difficulty::Medium),
+    (4705890435716616883, Difficulty::Medium),
+    (4957831142025573887, Difficulty::Medium),
+    (2539536812793602528, Difficulty::Hard),
+    (1237011886431954295, Difficulty::Medium),
+    (15923407598524927238, Difficulty::Easy),
+    (17676399912876237834, Difficulty::Medium),
+    (17107160546657310997, Difficulty::Hard),
+    (705549376362425742, Difficulty::Medium),
+    (515362969618663845, Difficulty::Medium),
+    (10777121537078537414, Difficulty::Medium),
+    (13259736665124071037, Difficulty::Hard),
+    (14406116699270683976, Difficulty::Hard),
+    (13821744310288879246, Difficulty::Easy),
+    (1866458441818505592, Difficulty::Medium),
+    (12239559916884061133, Difficulty::Medium),
+    (11995867806965781599, Difficulty::Easy),
+    (14837532726798939405, Difficulty::Hard),
+    (657863290214943922, Difficulty::Hard),
+    (403544306292563386, Difficulty::Easy),
+    (5802519240357966898, Difficulty::Easy),
+    (15662950245791355896, Difficulty::Easy),
+    (9320883766782987328, Difficulty::Hard),
+    (7414872499462654752, Difficulty::Easy),
+    (15072130226139088842, Difficulty::Easy),
+    (7935954002334263558, Difficulty::Hard),
+    (10196884111753752195, Difficulty::Medium),
+    (6894094667774030774, Difficulty::Hard),
+    (18254766720043515090, Difficulty::Easy),
+    (2491721883666043783, Difficulty::Easy),
+    (715637732776211684, Difficulty::Hard),
+    (5949310529699167819, Difficulty::Easy),
+    (4911656887362553958, Difficulty::Medium),
+    (5463529654144409602, Difficulty::Hard),
+    (15508362954674637919, Difficulty::Easy),
+    (14077532271770749741, Difficulty::Medium),
+    (4291908864802644237, Difficulty::Hard),
+    (15602498924289654882, Difficulty::Easy),
+    (15875952220339081210, Difficulty::Medium),
+];
+const WINNABLE_DRAW_THREE_SEEDS: &[(u64, Difficulty)] = &[
+    (11491721373609159005, Difficulty::Easy),
+    (1499751601245649308, Difficulty::Easy),
+    (5954713448187445940, Difficulty::Easy),
+    (1542844391795231216, Difficulty::Easy),
+    (10994088546977576021, Difficulty::Easy),
+    (14936440602159194164, Difficulty::Hard),
+    (3769008187841854481, Difficulty::Medium),
+    (4039962910052124132, Difficulty::Hard),
+    (5298413293935067076, Difficulty::Hard),
+    (4598339041518901811, Difficulty::Easy),
+    (17077966095238714961, Difficulty::Easy),
+    (13671010304086430007, Difficulty::Easy),
+    (14860221239204835809, Difficulty::Easy),
+    (4320969932239787639, Difficulty::Easy),
+    (11491721923857194404, Difficulty::Hard),
+    (16710933172462195321, Difficulty::Medium),
+    (16605662242981200987, Difficulty::Hard),
+    (10820783743816355085, Difficulty::Hard),
+    (11270181784669947560, Difficulty::Medium),
+    (8673346839662505723, Difficulty::Medium),
+    (11327352664917163240, Difficulty::Easy),
+    (13660115344460785989, Difficulty::Medium),
+    (10653222694625989981, Difficulty::Easy),
+    (14002268843960063933, Difficulty::Medium),
+    (5373215794009194921, Difficulty::Hard),
+    (7855674547468095954, Difficulty::Medium),
+    (5378713170138506841, Difficulty::Medium),
+    (17614708258278510651, Difficulty::Easy),
+    (15781553275730049641, Difficulty::Easy),
+    (13296682634416769030, Difficulty::Easy),
+    (12412365638174093404, Difficulty::Medium),
+    (14268555385895434656, Difficulty::Hard),
+    (4947501929663322398, Difficulty::Medium),
+    (1340207633301281247, Difficulty::Easy),
+    (11266167773888672627, Difficulty::Easy),
+    (249171039106922025, Difficulty::Easy),
+    (2885702412979681328, Difficulty::Easy),
+    (16277986417384955409, Difficulty::Medium),
+    (6465352520125529286, Difficulty::Easy),
+    (11942459909801037656, Difficulty::Hard),
+    (14093010802476332591, Difficulty::Hard),
+    (4754408130696899677, Difficulty::Medium),
+    (12765566793928973732, Difficulty::Hard),
+    (13433502436823316928, Difficulty::Hard),
+    (9323763395251109300, Difficulty::Hard),
+    (9865546647163237219, Difficulty::Hard),
+    (94645564884831011, Difficulty::Hard),
+    (8256001418966891320, Difficulty::Hard),
+    (273284438918132948, Difficulty::Hard),
+    (11771277453561331157, Difficulty::Easy),
+    (10938674340867186272, Difficulty::Medium),
+    (14175046669675637518, Difficulty::Medium),
+    (17311955608197217707, Difficulty::Medium),
+    (5817009185818528565, Difficulty::Easy),
+    (10976398708139967184, Difficulty::Medium),
+    (5025986653571785180, Difficulty::Easy),
+    (3048283614954634816, Difficulty::Hard),
+    (10708063701422785769, Difficulty::Hard),
+    (10088655457089424746, Difficulty::Hard),
+    (5460248186538099901, Difficulty::Easy),
+    (5951502876505275793, Difficulty::Hard),
+    (14025149942067609915, Difficulty::Medium),
+    (1947425638793678235, Difficulty::Hard),
+    (1932814896320767101, Difficulty::Hard),
+    (18230247567338925364, Difficulty::Hard),
+    (16063537985173244158, Difficulty::Medium),
+    (14823893857413240897, Difficulty::Easy),
+    (14734119209407604706, Difficulty::Hard),
+    (495701609919802395, Difficulty::Easy),
+    (487237116260516464, Difficulty::Hard),
+    (14772638531291265540, Difficulty::Hard),
+    (8491433486311780233, Difficulty::Easy),
+    (16628211185346198712, Difficulty::Medium),
+    (14621904648867625757, Difficulty::Hard),
+    (11121342153750371108, Difficulty::Hard),
+    (4956989711609768782, Difficulty::Hard),
+    (332429809295633929, Difficulty::Medium),
+    (3281346325672569838, Difficulty::Medium),
+    (12763479708204356814, Difficulty::Easy),
+    (9637683568016606698, Difficulty::Hard),
+    (13940757298171276825, Difficulty::Medium),
+    (5073017400234012621, Difficulty::Medium),
+    (17418194057697196149, Difficulty::Hard),
+    (1281787024391023083, Difficulty::Medium),
+    (58559879475928083, Difficulty::Hard),
+    (15548965121104329312, Difficulty::Easy),
+    (4692356787583490387, Difficulty::Easy),
+    (2575955914449745490, Difficulty::Easy),
+    (3620874484118786779, Difficulty::Hard),
+    (9285212232414082308, Difficulty::Easy),
+    (1952869648380193315, Difficulty::Hard),
+    (3786449755361863999, Difficulty::Easy),
+    (160526597083203686, Difficulty::Easy),
+    (12816217254741090300, Difficulty::Easy),
+    (3055824178406959459, Difficulty::Easy),
+    (16572198141231330579, Difficulty::Easy),
+];
+
+enum Candidates {
+    Random,
+    // Seeds counting up from the first one, so every search finds the same deal
+    Sequence { first: u64 },
+}
+
+/// Looks for a seed whose deal the solver can win, a few positions at a time so the page stays
+/// responsive. The stock can be turned over any number of times, as in the game.
+pub struct SeedSearch {
+    draw: Draw,
+    // Only deals with this rating are accepted, if there is one
+    difficulty: Option<Difficulty>,
+    candidates: Candidates,
+    seed: u64,
+    solver: Solver,
+    // When to stop searching and pick a seed from the table instead, in milliseconds
+    deadline: f64,
+}
+
+impl SeedSearch {
+    fn new(
+        seed: u64,
+        draw: Draw,
+        difficulty: Option<Difficulty>,
+        candidates: Candidates,
+        deadline: f64,
+    ) -> Self {
+        Self {
+            draw,
+            difficulty,
+            candidates,
+            seed,
+            solver: Self::solver(seed, draw),
+            deadline,
+        }
+    }
+
+    /// Searches random seeds, falling back to a known winnable seed once `deadline` has passed.
+    pub fn random(draw: Draw, difficulty: Option<Difficulty>, deadline: f64) -> Self {
+        Self::new(
+            deal::random_seed(),
+            draw,
+            difficulty,
+            Candidates::Random,
+            deadline,
+        )
+    }
+
+    /// Searches `first`, then the seeds after it, until one can be won. If none of the first
+    /// thousand can be, or `deadline` passes first, a known winnable seed is picked instead, the
+    /// same one every time for the same `first`.
+    pub fn sequence(first: u64, draw: Draw, deadline: f64) -> Self {
+        Self::new(first, draw, None, Candidates::Sequence { first }, deadline)
+    }
+
+    fn solver(seed: u64, draw: Draw) -> Solver {
+        Solver::new(seed, draw).with_node_limit(NODE_LIMIT)
+    }
+
+    pub fn draw(&self) -> Draw {
+        self.draw
+    }
+
+    fn accepts(&self, rating: Difficulty) -> bool {
+        self.difficulty.is_none() || self.difficulty == Some(rating)
+    }
+
+    fn known_seed(&self) -> (u64, Difficulty) {
+        let seeds = match self.draw {
+            Draw::One => WINNABLE_DRAW_ONE_SEEDS,
+            Draw::Three => WINNABLE_DRAW_THREE_SEEDS,
+        };
+        let matching = seeds
+            .iter()
+            .filter(|(_, rating)| self.accepts(*rating))
+            .collect::<Vec<_>>();
+        let pick = match self.candidates {
+            Candidates::Random => deal::random_seed(),
+            Candidates::Sequence { first } => first / SEQUENCE_LENGTH,
+        };
+        *matching[pick as usize % matching.len()]
+    }
+
+    /// Searches up to `node_budget` positions, returning the seed and its rating once a suitable
+    /// one is found.
+    pub fn step(&mut self, node_budget: usize, now: f64) -> Option<(u64, Difficulty)> {
+        if now >= self.deadline {
+            return Some(self.known_seed());
+        }
+        if let Candidates::Sequence { first } = self.candidates {
+            if self.seed.wrapping_sub(first) >= SEQUENCE_LENGTH {
+                return Some(self.known_seed());
+            }
+        }
+
+        let found = match self.solver.step(node_budget)? {
+            SolveResult::Solved(_) => {
+                let rating = Difficulty::rate(&self.solver.stats());
+                Some((self.seed, rating)).filter(|_| self.accepts(rating))
+            }
+            SolveResult::Unsolvable | SolveResult::GaveUp => None,
+        };
+
+        if found.is_none() {
+            self.seed = match self.candidates {
+                Candidates::Random => deal::random_seed(),
+                Candidates::Sequence { .. } => self.seed.wrapping_add(1),
+            };
+            self.solver = Self::solver(self.seed, self.draw);
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Game;
+
+    // Each seed in the table should still be won, with the same rating, by a full search. Some
+    // take more positions than a seed search spends on one seed. Only a sample is checked to
+    // keep the tests quick, and `solitaire-solve` can check the rest.
+    fn check_seeds(draw: Draw, seeds: &[(u64, Difficulty)]) {
+        for &(seed, rating) in seeds.iter().step_by(8) {
+            let mut solver = Solver::new(seed, draw);
+            let solution = match solver.step(usize::MAX) {
+                Some(SolveResult::Solved(solution)) => solution,
+                result => panic!("seed {} wasn't solved: {:?}", seed, result),
+            };
+            assert_eq!(
+                Difficulty::rate(&solver.stats()),
+                rating,
+                "seed {} changed rating",
+                seed
+            );
+
+            let mut game = Game::new(seed, draw);
+            for game_move in solution {
+                game.play(game_move).unwrap();
+            }
+            assert!(game.is_won(), "seed {}'s solution doesn't win", seed);
+        }
+    }
+
+    #[test]
+    fn draw_one_seeds_are_winnable() {
+        check_seeds(Draw::One, WINNABLE_DRAW_ONE_SEEDS);
+    }
+
+    #[test]
+    fn draw_three_seeds_are_winnable() {
+        check_seeds(Draw::Three, WINNABLE_DRAW_THREE_SEEDS);
+    }
+}